@@ -0,0 +1,72 @@
+//! Direct access to the ERC20 `allowances` dictionary.
+//!
+//! `ERC20::approve` always overwrites the allowance of the caller. Signed permits, relative
+//! allowance changes and spends that bypass `ERC20::transfer_from` write allowances here, using
+//! the same dictionary item keys.
+use alloc::{string::String, vec::Vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::{constants::ALLOWANCES_KEY_NAME, Address, Error, ERC20};
+use casper_types::{bytesrepr::ToBytes, U256};
+
+use crate::error::WcsprError;
+use crate::helpers::get_uref;
+
+fn make_dictionary_item_key(owner: Address, spender: Address) -> String {
+    let mut preimage = Vec::new();
+    preimage.append(&mut owner.to_bytes().unwrap_or_revert());
+    preimage.append(&mut spender.to_bytes().unwrap_or_revert());
+    let key_bytes = runtime::blake2b(&preimage);
+    hex::encode(&key_bytes)
+}
+
+/// Sets `amount` that `spender` is allowed to transfer from `owner`.
+pub fn write_allowance(owner: Address, spender: Address, amount: U256) {
+    storage::dictionary_put(
+        get_uref(ALLOWANCES_KEY_NAME),
+        &make_dictionary_item_key(owner, spender),
+        amount,
+    );
+}
+
+/// Adds `amount` to the allowance of `spender` over `owner` tokens, returns the new allowance.
+pub fn increase_allowance(owner: Address, spender: Address, amount: U256) -> U256 {
+    let allowance = ERC20::default()
+        .allowance(owner, spender)
+        .checked_add(amount)
+        .unwrap_or_revert_with(WcsprError::AmountOverflow);
+    write_allowance(owner, spender, allowance);
+    allowance
+}
+
+/// Subtracts `amount` from the allowance of `spender` over `owner` tokens, returns the new
+/// allowance.
+pub fn decrease_allowance(owner: Address, spender: Address, amount: U256) -> U256 {
+    let allowance = ERC20::default()
+        .allowance(owner, spender)
+        .checked_sub(amount)
+        .unwrap_or_revert_with(WcsprError::AllowanceUnderflow);
+    write_allowance(owner, spender, allowance);
+    allowance
+}
+
+/// Sets allowance of `spender` over `owner` tokens to `amount` only if the current allowance
+/// equals `expected_current`, so a spender could not use the old allowance in between.
+pub fn approve_if(owner: Address, spender: Address, expected_current: U256, amount: U256) {
+    if ERC20::default().allowance(owner, spender) != expected_current {
+        runtime::revert(WcsprError::AllowanceMismatch)
+    }
+    write_allowance(owner, spender, amount);
+}
+
+/// Spends `amount` of `spender` allowance over `owner` tokens the same way `transfer_from` does.
+pub fn spend_allowance(owner: Address, spender: Address, amount: U256) {
+    let allowance = ERC20::default()
+        .allowance(owner, spender)
+        .checked_sub(amount)
+        .unwrap_or_revert_with(Error::InsufficientAllowance);
+    write_allowance(owner, spender, allowance);
+}
@@ -0,0 +1,98 @@
+//! Allowlist of depositors for the guarded launch.
+//!
+//! Every allowlisted address belongs to a tier (e.g. retail, partner, market maker), each tier has
+//! its own max holding and per deposit cap. Tiers are numbered from 1, tier 0 means the address is
+//! not allowlisted. While the allowlist is enabled only allowlisted addresses could receive
+//! deposits, global deposit limits still apply on top of the tier caps.
+use alloc::string::ToString;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::{Address, ERC20};
+use casper_types::U256;
+
+use crate::error::WcsprError;
+use crate::helpers::{address_to_key, get_key, get_uref, set_key};
+
+pub const ALLOWLIST_ENABLED_KEY_NAME: &str = "allowlist_enabled";
+pub const ADDRESS_TIERS_DICT_NAME: &str = "address_tiers";
+pub const TIER_CAPS_DICT_NAME: &str = "tier_caps";
+
+/// Tier of addresses that are not allowlisted.
+pub const NO_TIER: u8 = 0;
+
+/// Creates disabled allowlist without tiers.
+pub fn init() {
+    storage::new_dictionary(ADDRESS_TIERS_DICT_NAME).unwrap_or_revert();
+    storage::new_dictionary(TIER_CAPS_DICT_NAME).unwrap_or_revert();
+    set_enabled(false);
+}
+
+pub fn set_enabled(enabled: bool) {
+    set_key(ALLOWLIST_ENABLED_KEY_NAME, enabled);
+}
+
+pub fn is_enabled() -> bool {
+    get_key(ALLOWLIST_ENABLED_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized)
+}
+
+/// Sets `(max_holding, max_deposit)` caps of `tier`.
+pub fn set_tier_caps(tier: u8, max_holding: U256, max_deposit: U256) {
+    if tier == NO_TIER {
+        runtime::revert(WcsprError::UnknownTier)
+    }
+    storage::dictionary_put(
+        get_uref(TIER_CAPS_DICT_NAME),
+        &tier.to_string(),
+        (max_holding, max_deposit),
+    );
+}
+
+/// Returns `(max_holding, max_deposit)` caps of `tier`, reverting on tiers without caps.
+pub fn tier_caps(tier: u8) -> (U256, U256) {
+    storage::dictionary_get(get_uref(TIER_CAPS_DICT_NAME), &tier.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_revert_with(WcsprError::UnknownTier)
+}
+
+/// Moves `address` to `tier`, [`NO_TIER`] removes it from the allowlist.
+pub fn set_address_tier(address: Address, tier: u8) {
+    if tier != NO_TIER {
+        // Only tiers with caps could be assigned
+        tier_caps(tier);
+    }
+    storage::dictionary_put(
+        get_uref(ADDRESS_TIERS_DICT_NAME),
+        &address_to_key(&address),
+        tier,
+    );
+}
+
+pub fn tier_of(address: Address) -> u8 {
+    storage::dictionary_get(get_uref(ADDRESS_TIERS_DICT_NAME), &address_to_key(&address))
+        .unwrap_or_revert()
+        .unwrap_or(NO_TIER)
+}
+
+/// Reverts if the allowlist is enabled and minting `amount` WCSPR to `owner` is not allowed by
+/// its tier.
+pub fn check_deposit(owner: Address, amount: U256) {
+    if !is_enabled() {
+        return;
+    }
+
+    let tier = tier_of(owner);
+    if tier == NO_TIER {
+        runtime::revert(WcsprError::NotAllowlisted)
+    }
+
+    let (max_holding, max_deposit) = tier_caps(tier);
+    if amount > max_deposit {
+        runtime::revert(WcsprError::ExceedTierDepositCap)
+    }
+    if ERC20::default().balance_of(owner) + amount > max_holding {
+        runtime::revert(WcsprError::ExceedTierHoldingCap)
+    }
+}
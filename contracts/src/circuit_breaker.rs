@@ -0,0 +1,99 @@
+//! Withdrawal circuit breaker.
+//!
+//! Limits total CSPR withdrawn within a sliding time window (e.g. an era), measured with block
+//! time. Capacity to withdraw refills continuously by `limit * elapsed / window`, up to `limit`, so
+//! no more than `limit` could be withdrawn within any `window`. Withdrawals over the capacity are
+//! reverted. If configured, withdrawals are also paused once the capacity left falls below a floor.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{U256, U512};
+
+use crate::error::WcsprError;
+use crate::events::{emit, WcsprEvent};
+use crate::helpers::{get_key, set_key};
+use crate::pause::{self, Operation};
+
+pub const WITHDRAWAL_WINDOW_KEY_NAME: &str = "withdrawal_window";
+pub const WITHDRAWAL_LIMIT_KEY_NAME: &str = "withdrawal_limit";
+pub const WITHDRAWAL_PAUSE_FLOOR_KEY_NAME: &str = "withdrawal_pause_floor";
+pub const WITHDRAWN_IN_WINDOW_KEY_NAME: &str = "withdrawn_in_window";
+pub const WITHDRAWN_UPDATED_AT_KEY_NAME: &str = "withdrawn_updated_at";
+
+/// Creates disabled circuit breaker: zero limit means no limit.
+pub fn init() {
+    set_config(0, U512::zero(), U512::zero());
+    set_key(WITHDRAWN_IN_WINDOW_KEY_NAME, U512::zero());
+    set_key(WITHDRAWN_UPDATED_AT_KEY_NAME, 0u64);
+}
+
+/// Sets max CSPR withdrawn per `window` milliseconds, zero `limit` disables the circuit breaker.
+///
+/// Withdrawals are paused when less than `pause_floor` could still be withdrawn, zero
+/// `pause_floor` never pauses them.
+pub fn set_config(window: u64, limit: U512, pause_floor: U512) {
+    if window == 0 && !limit.is_zero() {
+        runtime::revert(WcsprError::InvalidWindow)
+    }
+    set_key(WITHDRAWAL_WINDOW_KEY_NAME, window);
+    set_key(WITHDRAWAL_LIMIT_KEY_NAME, limit);
+    set_key(WITHDRAWAL_PAUSE_FLOOR_KEY_NAME, pause_floor);
+}
+
+fn limit() -> U512 {
+    get_key(WITHDRAWAL_LIMIT_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized)
+}
+
+/// Returns CSPR withdrawn that still counts against `limit` at the current block time.
+fn withdrawn_in_window(limit: U512) -> U512 {
+    let window: u64 = get_key(WITHDRAWAL_WINDOW_KEY_NAME).unwrap_or_revert();
+    let withdrawn: U512 = get_key(WITHDRAWN_IN_WINDOW_KEY_NAME).unwrap_or_revert();
+    let updated_at: u64 = get_key(WITHDRAWN_UPDATED_AT_KEY_NAME).unwrap_or_revert();
+
+    let elapsed = u64::from(runtime::get_blocktime()).saturating_sub(updated_at);
+    // Overflow means that much time has passed that the whole limit is refilled
+    let refilled = limit
+        .checked_mul(U512::from(elapsed))
+        .map(|amount| amount / U512::from(window))
+        .unwrap_or_else(U512::max_value);
+    withdrawn.saturating_sub(refilled)
+}
+
+/// Returns CSPR that could still be withdrawn at the current block time.
+pub fn capacity() -> U512 {
+    let limit = limit();
+    if limit.is_zero() {
+        return U512::max_value();
+    }
+    limit.saturating_sub(withdrawn_in_window(limit))
+}
+
+/// Records withdrawal of `cspr_amount`, reverts if it is over the current capacity.
+///
+/// Pauses withdrawals if the capacity left afterwards is below the pause floor.
+pub fn record_withdrawal(cspr_amount: U512) {
+    pause::require_not_paused(Operation::Withdraw);
+
+    let limit = limit();
+    if limit.is_zero() {
+        return;
+    }
+
+    let withdrawn = withdrawn_in_window(limit) + cspr_amount;
+    if withdrawn > limit {
+        runtime::revert(WcsprError::WithdrawalLimitExceeded)
+    }
+
+    set_key(WITHDRAWN_IN_WINDOW_KEY_NAME, withdrawn);
+    set_key(
+        WITHDRAWN_UPDATED_AT_KEY_NAME,
+        u64::from(runtime::get_blocktime()),
+    );
+
+    let capacity = limit - withdrawn;
+    let pause_floor: U512 = get_key(WITHDRAWAL_PAUSE_FLOOR_KEY_NAME).unwrap_or_revert();
+    if capacity < pause_floor {
+        pause::set_paused(Operation::Withdraw, true);
+        emit(WcsprEvent::WithdrawalsAutoPaused {
+            capacity: U256::from(capacity.as_u128()),
+        });
+    }
+}
@@ -1,16 +1,123 @@
-use alloc::{string::String, vec};
+use alloc::{string::String, vec, vec::Vec};
 
 use casper_erc20::entry_points;
 
+use casper_erc20::Address;
+
 use casper_types::{
-    URef, U512, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
+    account::AccountHash, bytesrepr::Bytes, contracts::NamedKeys, CLType, CLTyped,
+    ContractPackageHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter,
+    PublicKey, URef, U256, U512,
 };
 
+pub fn batch_transfer() -> EntryPoint {
+    EntryPoint::new(
+        String::from("batch_transfer"),
+        vec![Parameter::new(
+            "recipients",
+            Vec::<(Address, U256)>::cl_type(),
+        )],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn batch_transfer_from() -> EntryPoint {
+    EntryPoint::new(
+        String::from("batch_transfer_from"),
+        vec![
+            Parameter::new("owner", Address::cl_type()),
+            Parameter::new("recipients", Vec::<(Address, U256)>::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn increase_allowance() -> EntryPoint {
+    EntryPoint::new(
+        String::from("increase_allowance"),
+        vec![
+            Parameter::new("spender", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn decrease_allowance() -> EntryPoint {
+    EntryPoint::new(
+        String::from("decrease_allowance"),
+        vec![
+            Parameter::new("spender", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn approve_if() -> EntryPoint {
+    EntryPoint::new(
+        String::from("approve_if"),
+        vec![
+            Parameter::new("spender", Address::cl_type()),
+            Parameter::new("expected_current", U256::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn permit() -> EntryPoint {
+    EntryPoint::new(
+        String::from("permit"),
+        vec![
+            Parameter::new("owner", PublicKey::cl_type()),
+            Parameter::new("spender", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("deadline", u64::cl_type()),
+            Parameter::new("signature", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn nonces() -> EntryPoint {
+    EntryPoint::new(
+        String::from("nonces"),
+        vec![Parameter::new("owner", PublicKey::cl_type())],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
 pub fn deposit() -> EntryPoint {
     EntryPoint::new(
         String::from("deposit"),
+        vec![Parameter::new("tmp_purse", URef::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn deposit_for() -> EntryPoint {
+    EntryPoint::new(
+        String::from("deposit_for"),
         vec![
             Parameter::new("tmp_purse", URef::cl_type()),
+            Parameter::new("recipient", Address::cl_type()),
         ],
         CLType::Unit,
         EntryPointAccess::Public,
@@ -21,7 +128,31 @@ pub fn deposit() -> EntryPoint {
 pub fn withdraw() -> EntryPoint {
     EntryPoint::new(
         String::from("withdraw"),
+        vec![Parameter::new("cspr_amount", U512::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn withdraw_to() -> EntryPoint {
+    EntryPoint::new(
+        String::from("withdraw_to"),
+        vec![
+            Parameter::new("recipient", AccountHash::cl_type()),
+            Parameter::new("cspr_amount", U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn withdraw_to_purse() -> EntryPoint {
+    EntryPoint::new(
+        String::from("withdraw_to_purse"),
         vec![
+            Parameter::new("purse", URef::cl_type()),
             Parameter::new("cspr_amount", U512::cl_type()),
         ],
         CLType::Unit,
@@ -30,10 +161,425 @@ pub fn withdraw() -> EntryPoint {
     )
 }
 
+pub fn check_invariants() -> EntryPoint {
+    EntryPoint::new(
+        String::from("check_invariants"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn sync() -> EntryPoint {
+    EntryPoint::new(
+        String::from("sync"),
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn skim() -> EntryPoint {
+    EntryPoint::new(
+        String::from("skim"),
+        vec![Parameter::new("recipient", AccountHash::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn flash_loan() -> EntryPoint {
+    EntryPoint::new(
+        String::from("flash_loan"),
+        vec![
+            Parameter::new("receiver", ContractPackageHash::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn flash_fee() -> EntryPoint {
+    EntryPoint::new(
+        String::from("flash_fee"),
+        vec![Parameter::new("amount", U256::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn max_flash_loan() -> EntryPoint {
+    EntryPoint::new(
+        String::from("max_flash_loan"),
+        vec![],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_flash_loan_config() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_flash_loan_config"),
+        vec![
+            Parameter::new("fee_bps", u32::cl_type()),
+            Parameter::new("max_amount", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn snapshot() -> EntryPoint {
+    EntryPoint::new(
+        String::from("snapshot"),
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn balance_of_at() -> EntryPoint {
+    EntryPoint::new(
+        String::from("balance_of_at"),
+        vec![
+            Parameter::new("address", Address::cl_type()),
+            Parameter::new("snapshot_id", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn total_supply_at() -> EntryPoint {
+    EntryPoint::new(
+        String::from("total_supply_at"),
+        vec![Parameter::new("snapshot_id", u64::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn delegate() -> EntryPoint {
+    EntryPoint::new(
+        String::from("delegate"),
+        vec![Parameter::new("delegatee", Address::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn delegates() -> EntryPoint {
+    EntryPoint::new(
+        String::from("delegates"),
+        vec![Parameter::new("account", Address::cl_type())],
+        Option::<Address>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn get_votes() -> EntryPoint {
+    EntryPoint::new(
+        String::from("get_votes"),
+        vec![Parameter::new("account", Address::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn get_past_votes() -> EntryPoint {
+    EntryPoint::new(
+        String::from("get_past_votes"),
+        vec![
+            Parameter::new("account", Address::cl_type()),
+            Parameter::new("timestamp", u64::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn freeze() -> EntryPoint {
+    EntryPoint::new(
+        String::from("freeze"),
+        vec![Parameter::new("address", Address::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn unfreeze() -> EntryPoint {
+    EntryPoint::new(
+        String::from("unfreeze"),
+        vec![Parameter::new("address", Address::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn is_frozen() -> EntryPoint {
+    EntryPoint::new(
+        String::from("is_frozen"),
+        vec![Parameter::new("address", Address::cl_type())],
+        bool::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_withdrawal_limit() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_withdrawal_limit"),
+        vec![
+            Parameter::new("window", u64::cl_type()),
+            Parameter::new("limit", U512::cl_type()),
+            Parameter::new("pause_floor", U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn withdrawal_capacity() -> EntryPoint {
+    EntryPoint::new(
+        String::from("withdrawal_capacity"),
+        vec![],
+        U512::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn fee_config() -> EntryPoint {
+    EntryPoint::new(
+        String::from("fee_config"),
+        vec![],
+        <(u32, u32, AccountHash)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_fee_config() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_fee_config"),
+        vec![
+            Parameter::new("deposit_fee_bps", u32::cl_type()),
+            Parameter::new("withdraw_fee_bps", u32::cl_type()),
+            Parameter::new("treasury", AccountHash::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_limits() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_limits"),
+        vec![
+            Parameter::new("user_limit", U256::cl_type()),
+            Parameter::new("contract_limit", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn get_limits() -> EntryPoint {
+    EntryPoint::new(
+        String::from("get_limits"),
+        vec![],
+        <(U256, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn remaining_quota() -> EntryPoint {
+    EntryPoint::new(
+        String::from("remaining_quota"),
+        vec![Parameter::new("address", Address::cl_type())],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_allowlist_enabled() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_allowlist_enabled"),
+        vec![Parameter::new("enabled", bool::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_tier_caps() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_tier_caps"),
+        vec![
+            Parameter::new("tier", u8::cl_type()),
+            Parameter::new("max_holding", U256::cl_type()),
+            Parameter::new("max_deposit", U256::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn set_address_tier() -> EntryPoint {
+    EntryPoint::new(
+        String::from("set_address_tier"),
+        vec![
+            Parameter::new("address", Address::cl_type()),
+            Parameter::new("tier", u8::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn tier_of() -> EntryPoint {
+    EntryPoint::new(
+        String::from("tier_of"),
+        vec![Parameter::new("address", Address::cl_type())],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn tier_caps() -> EntryPoint {
+    EntryPoint::new(
+        String::from("tier_caps"),
+        vec![Parameter::new("tier", u8::cl_type())],
+        <(U256, U256)>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn grant_role() -> EntryPoint {
+    EntryPoint::new(
+        String::from("grant_role"),
+        vec![
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("address", Address::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn revoke_role() -> EntryPoint {
+    EntryPoint::new(
+        String::from("revoke_role"),
+        vec![
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("address", Address::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn has_role() -> EntryPoint {
+    EntryPoint::new(
+        String::from("has_role"),
+        vec![
+            Parameter::new("role", String::cl_type()),
+            Parameter::new("address", Address::cl_type()),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn pause() -> EntryPoint {
+    EntryPoint::new(
+        String::from("pause"),
+        vec![Parameter::new("operations", Vec::<String>::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn unpause() -> EntryPoint {
+    EntryPoint::new(
+        String::from("unpause"),
+        vec![Parameter::new("operations", Vec::<String>::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn is_paused() -> EntryPoint {
+    EntryPoint::new(
+        String::from("is_paused"),
+        vec![Parameter::new("operation", String::cl_type())],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn named_keys_for_upgrade() -> EntryPoint {
+    EntryPoint::new(
+        String::from("named_keys_for_upgrade"),
+        vec![Parameter::new("package_access", URef::cl_type())],
+        NamedKeys::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+pub fn migrate() -> EntryPoint {
+    EntryPoint::new(
+        String::from("migrate"),
+        vec![Parameter::new("chain_name", String::cl_type())],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
 pub fn init() -> EntryPoint {
     EntryPoint::new(
         String::from("init"),
-        vec![],
+        vec![
+            Parameter::new("user_limit", U256::cl_type()),
+            Parameter::new("contract_limit", U256::cl_type()),
+            Parameter::new("chain_name", String::cl_type()),
+            Parameter::new("unwrap_self_transfers", bool::cl_type()),
+        ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -42,8 +588,55 @@ pub fn init() -> EntryPoint {
 
 pub fn default() -> EntryPoints {
     let mut contract_entry_points = entry_points::default();
+    contract_entry_points.add_entry_point(batch_transfer());
+    contract_entry_points.add_entry_point(batch_transfer_from());
+    contract_entry_points.add_entry_point(increase_allowance());
+    contract_entry_points.add_entry_point(decrease_allowance());
+    contract_entry_points.add_entry_point(approve_if());
+    contract_entry_points.add_entry_point(permit());
+    contract_entry_points.add_entry_point(nonces());
     contract_entry_points.add_entry_point(deposit());
+    contract_entry_points.add_entry_point(deposit_for());
     contract_entry_points.add_entry_point(withdraw());
+    contract_entry_points.add_entry_point(withdraw_to());
+    contract_entry_points.add_entry_point(withdraw_to_purse());
+    contract_entry_points.add_entry_point(check_invariants());
+    contract_entry_points.add_entry_point(sync());
+    contract_entry_points.add_entry_point(skim());
+    contract_entry_points.add_entry_point(flash_loan());
+    contract_entry_points.add_entry_point(flash_fee());
+    contract_entry_points.add_entry_point(max_flash_loan());
+    contract_entry_points.add_entry_point(set_flash_loan_config());
+    contract_entry_points.add_entry_point(snapshot());
+    contract_entry_points.add_entry_point(balance_of_at());
+    contract_entry_points.add_entry_point(total_supply_at());
+    contract_entry_points.add_entry_point(delegate());
+    contract_entry_points.add_entry_point(delegates());
+    contract_entry_points.add_entry_point(get_votes());
+    contract_entry_points.add_entry_point(get_past_votes());
+    contract_entry_points.add_entry_point(freeze());
+    contract_entry_points.add_entry_point(unfreeze());
+    contract_entry_points.add_entry_point(is_frozen());
+    contract_entry_points.add_entry_point(set_withdrawal_limit());
+    contract_entry_points.add_entry_point(withdrawal_capacity());
+    contract_entry_points.add_entry_point(fee_config());
+    contract_entry_points.add_entry_point(set_fee_config());
+    contract_entry_points.add_entry_point(set_limits());
+    contract_entry_points.add_entry_point(get_limits());
+    contract_entry_points.add_entry_point(remaining_quota());
+    contract_entry_points.add_entry_point(set_allowlist_enabled());
+    contract_entry_points.add_entry_point(set_tier_caps());
+    contract_entry_points.add_entry_point(set_address_tier());
+    contract_entry_points.add_entry_point(tier_of());
+    contract_entry_points.add_entry_point(tier_caps());
+    contract_entry_points.add_entry_point(grant_role());
+    contract_entry_points.add_entry_point(revoke_role());
+    contract_entry_points.add_entry_point(has_role());
+    contract_entry_points.add_entry_point(pause());
+    contract_entry_points.add_entry_point(unpause());
+    contract_entry_points.add_entry_point(is_paused());
+    contract_entry_points.add_entry_point(named_keys_for_upgrade());
+    contract_entry_points.add_entry_point(migrate());
     contract_entry_points.add_entry_point(init());
     contract_entry_points
-}
\ No newline at end of file
+}
@@ -0,0 +1,80 @@
+use casper_types::ApiError;
+
+/// Errors reverted by the WCSPR contract entry points.
+///
+/// Each variant is mapped to a distinct [`ApiError::User`] code, so callers could tell the exact
+/// failure reason from the deploy execution result.
+pub enum WcsprError {
+    /// Deposit would exceed the per user limit.
+    ExceedUserLimit = 0,
+    /// Deposit would exceed the contract (total supply) limit.
+    ExceedContractLimit = 1,
+    /// Caller does not hold enough WCSPR.
+    InsufficientBalance = 2,
+    /// Contract main purse does not hold enough CSPR.
+    InsufficientReserves = 3,
+    /// Moving CSPR between purses failed.
+    PurseTransferFailed = 4,
+    /// Amount of zero was passed.
+    ZeroAmount = 5,
+    /// Amount does not fit into WCSPR supply.
+    AmountOverflow = 6,
+    /// Contract `init` has not been called yet.
+    NotInitialized = 7,
+    /// Caller does not hold the role required by the entry point.
+    MissingRole = 8,
+    /// Unknown role name was passed.
+    UnknownRole = 9,
+    /// Operation is paused.
+    ContractPaused = 10,
+    /// Unknown operation name was passed.
+    UnknownOperation = 11,
+    /// Stored storage version is newer than the contract supports.
+    UnsupportedStorageVersion = 12,
+    /// Flash loan amount is above the max flash loan.
+    ExceedFlashLoanLimit = 13,
+    /// Flash loan receiver does not hold `amount + fee` at the end of the loan.
+    FlashLoanNotRepaid = 14,
+    /// Total supply exceeds CSPR reserves.
+    InvariantViolated = 15,
+    /// Fee is above the allowed maximum.
+    FeeTooHigh = 16,
+    /// Permit deadline has passed.
+    PermitExpired = 17,
+    /// Permit signature does not match the owner public key.
+    InvalidSignature = 18,
+    /// Allowance is lower than the decreased amount.
+    AllowanceUnderflow = 19,
+    /// Current allowance differs from the expected one.
+    AllowanceMismatch = 20,
+    /// WCSPR was sent to the WCSPR contract package and could not be unwrapped.
+    SelfTransfer = 21,
+    /// Batch has more transfers than allowed.
+    BatchTooLarge = 22,
+    /// Snapshot with the passed id was not taken yet.
+    SnapshotNotFound = 23,
+    /// Past votes were requested for a block time that is not in the past yet.
+    FutureLookup = 24,
+    /// Address is frozen.
+    AddressFrozen = 25,
+    /// Withdrawal would exceed the withdrawal limit of the current window.
+    WithdrawalLimitExceeded = 26,
+    /// Deposit recipient is not allowlisted.
+    NotAllowlisted = 27,
+    /// Deposit is above the per deposit cap of the recipient tier.
+    ExceedTierDepositCap = 28,
+    /// Deposit would exceed the max holding of the recipient tier.
+    ExceedTierHoldingCap = 29,
+    /// Tier has no caps set.
+    UnknownTier = 30,
+    /// URef passed as the package access URef is not the one kept by the installer.
+    InvalidPackageAccess = 31,
+    /// Withdrawal limit is set without a window.
+    InvalidWindow = 32,
+}
+
+impl From<WcsprError> for ApiError {
+    fn from(error: WcsprError) -> ApiError {
+        ApiError::User(error as u16)
+    }
+}
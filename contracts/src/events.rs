@@ -0,0 +1,213 @@
+//! Append-only event store following the Casper Event Standard layout.
+//!
+//! Events are kept in the `__events` dictionary keyed by their sequence number, the number of
+//! emitted events is kept in `__events_length` and the schema of every event in `__events_schema`.
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use casper_contract::{contract_api::storage, unwrap_or_revert::UnwrapOrRevert};
+use casper_erc20::Address;
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    CLTyped, Parameter, U256,
+};
+
+use crate::helpers::{get_key, get_uref, set_key};
+
+pub const EVENTS_DICT_NAME: &str = "__events";
+pub const EVENTS_LENGTH_KEY_NAME: &str = "__events_length";
+pub const EVENTS_SCHEMA_KEY_NAME: &str = "__events_schema";
+pub const EVENTS_CES_VERSION_KEY_NAME: &str = "__events_ces_version";
+
+const CES_VERSION: &str = "0.1.0";
+const EVENT_PREFIX: &str = "event_";
+
+pub enum WcsprEvent {
+    Deposit {
+        owner: Address,
+        amount: U256,
+        fee: U256,
+    },
+    Withdrawal {
+        owner: Address,
+        amount: U256,
+        fee: U256,
+    },
+    Transfer {
+        sender: Address,
+        recipient: Address,
+        amount: U256,
+    },
+    Approval {
+        owner: Address,
+        spender: Address,
+        amount: U256,
+    },
+    FlashLoan {
+        receiver: Address,
+        amount: U256,
+        fee: U256,
+    },
+    Frozen {
+        address: Address,
+    },
+    Unfrozen {
+        address: Address,
+    },
+    WithdrawalsAutoPaused {
+        capacity: U256,
+    },
+}
+
+impl WcsprEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            WcsprEvent::Deposit { .. } => "Deposit",
+            WcsprEvent::Withdrawal { .. } => "Withdrawal",
+            WcsprEvent::Transfer { .. } => "Transfer",
+            WcsprEvent::Approval { .. } => "Approval",
+            WcsprEvent::FlashLoan { .. } => "FlashLoan",
+            WcsprEvent::Frozen { .. } => "Frozen",
+            WcsprEvent::Unfrozen { .. } => "Unfrozen",
+            WcsprEvent::WithdrawalsAutoPaused { .. } => "WithdrawalsAutoPaused",
+        }
+    }
+
+    /// Serializes event as the prefixed event name followed by its fields.
+    fn serialize(&self) -> Bytes {
+        let mut name = String::from(EVENT_PREFIX);
+        name.push_str(self.name());
+
+        let mut bytes = name.to_bytes().unwrap_or_revert();
+        match self {
+            WcsprEvent::Deposit { owner, amount, fee }
+            | WcsprEvent::Withdrawal { owner, amount, fee } => {
+                bytes.append(&mut owner.to_bytes().unwrap_or_revert());
+                bytes.append(&mut amount.to_bytes().unwrap_or_revert());
+                bytes.append(&mut fee.to_bytes().unwrap_or_revert());
+            }
+            WcsprEvent::Transfer {
+                sender,
+                recipient,
+                amount,
+            } => {
+                bytes.append(&mut sender.to_bytes().unwrap_or_revert());
+                bytes.append(&mut recipient.to_bytes().unwrap_or_revert());
+                bytes.append(&mut amount.to_bytes().unwrap_or_revert());
+            }
+            WcsprEvent::Approval {
+                owner,
+                spender,
+                amount,
+            } => {
+                bytes.append(&mut owner.to_bytes().unwrap_or_revert());
+                bytes.append(&mut spender.to_bytes().unwrap_or_revert());
+                bytes.append(&mut amount.to_bytes().unwrap_or_revert());
+            }
+            WcsprEvent::FlashLoan {
+                receiver,
+                amount,
+                fee,
+            } => {
+                bytes.append(&mut receiver.to_bytes().unwrap_or_revert());
+                bytes.append(&mut amount.to_bytes().unwrap_or_revert());
+                bytes.append(&mut fee.to_bytes().unwrap_or_revert());
+            }
+            WcsprEvent::Frozen { address } | WcsprEvent::Unfrozen { address } => {
+                bytes.append(&mut address.to_bytes().unwrap_or_revert());
+            }
+            WcsprEvent::WithdrawalsAutoPaused { capacity } => {
+                bytes.append(&mut capacity.to_bytes().unwrap_or_revert());
+            }
+        }
+        Bytes::from(bytes)
+    }
+}
+
+fn schemas() -> BTreeMap<String, Vec<Parameter>> {
+    let mut schemas = BTreeMap::new();
+    schemas.insert(
+        "Deposit".to_string(),
+        vec![
+            Parameter::new("owner", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("fee", U256::cl_type()),
+        ],
+    );
+    schemas.insert(
+        "Withdrawal".to_string(),
+        vec![
+            Parameter::new("owner", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("fee", U256::cl_type()),
+        ],
+    );
+    schemas.insert(
+        "Transfer".to_string(),
+        vec![
+            Parameter::new("sender", Address::cl_type()),
+            Parameter::new("recipient", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+    );
+    schemas.insert(
+        "Approval".to_string(),
+        vec![
+            Parameter::new("owner", Address::cl_type()),
+            Parameter::new("spender", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+        ],
+    );
+    schemas.insert(
+        "FlashLoan".to_string(),
+        vec![
+            Parameter::new("receiver", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("fee", U256::cl_type()),
+        ],
+    );
+    schemas.insert(
+        "Frozen".to_string(),
+        vec![Parameter::new("address", Address::cl_type())],
+    );
+    schemas.insert(
+        "Unfrozen".to_string(),
+        vec![Parameter::new("address", Address::cl_type())],
+    );
+    schemas.insert(
+        "WithdrawalsAutoPaused".to_string(),
+        vec![Parameter::new("capacity", U256::cl_type())],
+    );
+    schemas
+}
+
+/// Creates event store named keys in the current contract context.
+pub fn init() {
+    storage::new_dictionary(EVENTS_DICT_NAME).unwrap_or_revert();
+    set_key(EVENTS_LENGTH_KEY_NAME, 0u32);
+    set_schema();
+    set_key(EVENTS_CES_VERSION_KEY_NAME, String::from(CES_VERSION));
+}
+
+/// Saves schema of every event, should be called again whenever events are changed.
+pub fn set_schema() {
+    set_key(
+        EVENTS_SCHEMA_KEY_NAME,
+        Bytes::from(schemas().to_bytes().unwrap_or_revert()),
+    );
+}
+
+/// Appends `event` to the event store.
+pub fn emit(event: WcsprEvent) {
+    let events_length: u32 = get_key(EVENTS_LENGTH_KEY_NAME).unwrap_or_revert();
+    storage::dictionary_put(
+        get_uref(EVENTS_DICT_NAME),
+        &events_length.to_string(),
+        event.serialize(),
+    );
+    set_key(EVENTS_LENGTH_KEY_NAME, events_length + 1);
+}
@@ -0,0 +1,65 @@
+//! Deposit and withdraw fees paid to the treasury account.
+//!
+//! Fees are taken in CSPR, so every WCSPR stays backed 1:1: deposits mint WCSPR only for CSPR left
+//! after the fee, withdrawals burn the whole amount but pay out CSPR less the fee.
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{account::AccountHash, URef, U512};
+
+use crate::error::WcsprError;
+use crate::helpers::{get_key, set_key, BPS_DENOMINATOR};
+
+pub const DEPOSIT_FEE_BPS_KEY_NAME: &str = "deposit_fee_bps";
+pub const WITHDRAW_FEE_BPS_KEY_NAME: &str = "withdraw_fee_bps";
+pub const TREASURY_KEY_NAME: &str = "treasury";
+
+/// Max fee is 5%.
+pub const MAX_FEE_BPS: u32 = 500;
+
+/// Creates zero fees config paid to `treasury`.
+pub fn init(treasury: AccountHash) {
+    set_config(0, 0, treasury);
+}
+
+pub fn set_config(deposit_fee_bps: u32, withdraw_fee_bps: u32, treasury: AccountHash) {
+    if deposit_fee_bps > MAX_FEE_BPS || withdraw_fee_bps > MAX_FEE_BPS {
+        runtime::revert(WcsprError::FeeTooHigh)
+    }
+    set_key(DEPOSIT_FEE_BPS_KEY_NAME, deposit_fee_bps);
+    set_key(WITHDRAW_FEE_BPS_KEY_NAME, withdraw_fee_bps);
+    set_key(TREASURY_KEY_NAME, treasury);
+}
+
+/// Returns `(deposit_fee_bps, withdraw_fee_bps, treasury)` stored in the contract.
+pub fn get_config() -> (u32, u32, AccountHash) {
+    let deposit_fee_bps: u32 = get_key(DEPOSIT_FEE_BPS_KEY_NAME).unwrap_or_revert();
+    let withdraw_fee_bps: u32 = get_key(WITHDRAW_FEE_BPS_KEY_NAME).unwrap_or_revert();
+    let treasury: AccountHash = get_key(TREASURY_KEY_NAME).unwrap_or_revert();
+    (deposit_fee_bps, withdraw_fee_bps, treasury)
+}
+
+fn fee(cspr_amount: U512, fee_bps: u32) -> U512 {
+    cspr_amount * U512::from(fee_bps) / U512::from(BPS_DENOMINATOR)
+}
+
+pub fn deposit_fee(cspr_amount: U512) -> U512 {
+    let (deposit_fee_bps, _, _) = get_config();
+    fee(cspr_amount, deposit_fee_bps)
+}
+
+pub fn withdraw_fee(cspr_amount: U512) -> U512 {
+    let (_, withdraw_fee_bps, _) = get_config();
+    fee(cspr_amount, withdraw_fee_bps)
+}
+
+/// Sends `fee` of CSPR from `purse` to the treasury account.
+pub fn pay_fee(purse: URef, fee: U512) {
+    if fee.is_zero() {
+        return;
+    }
+    let (_, _, treasury) = get_config();
+    system::transfer_from_purse_to_account(purse, treasury, fee, None)
+        .unwrap_or_revert_with(WcsprError::PurseTransferFailed);
+}
@@ -0,0 +1,96 @@
+//! ERC-3156 style flash minting of WCSPR.
+//!
+//! Receiver contract gets freshly minted WCSPR, its `on_flash_loan` entry point is called and
+//! `amount + fee` is burned from its balance afterwards. The fee is not backed by any WCSPR
+//! anymore and stays in the contract main purse as excess reserves.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_erc20::{Address, ERC20};
+use casper_types::{bytesrepr::Bytes, runtime_args, ContractPackageHash, RuntimeArgs, U256};
+
+use crate::error::WcsprError;
+use crate::events::{emit, WcsprEvent};
+use crate::helpers::{get_immediate_caller_address, get_key, set_key, BPS_DENOMINATOR};
+use crate::{freeze, reserves, snapshots, votes};
+
+pub const FLASH_FEE_BPS_KEY_NAME: &str = "flash_fee_bps";
+pub const MAX_FLASH_LOAN_KEY_NAME: &str = "max_flash_loan";
+
+/// Creates disabled flash loan config: zero fee and zero max amount.
+pub fn init() {
+    set_config(0, U256::zero());
+}
+
+pub fn set_config(fee_bps: u32, max_amount: U256) {
+    if fee_bps > BPS_DENOMINATOR {
+        runtime::revert(WcsprError::FeeTooHigh)
+    }
+    set_key(FLASH_FEE_BPS_KEY_NAME, fee_bps);
+    set_key(MAX_FLASH_LOAN_KEY_NAME, max_amount);
+}
+
+pub fn max_flash_loan() -> U256 {
+    get_key(MAX_FLASH_LOAN_KEY_NAME).unwrap_or_default()
+}
+
+/// Returns fee of borrowing `amount`, reverting with [`WcsprError::AmountOverflow`] on amounts too
+/// big to take the fee from.
+pub fn flash_fee(amount: U256) -> U256 {
+    let fee_bps: u32 = get_key(FLASH_FEE_BPS_KEY_NAME).unwrap_or_default();
+    amount
+        .checked_mul(U256::from(fee_bps))
+        .unwrap_or_revert_with(WcsprError::AmountOverflow)
+        / U256::from(BPS_DENOMINATOR)
+}
+
+/// Lends `amount` of freshly minted WCSPR to `receiver` for the duration of its `on_flash_loan`
+/// call, then burns `amount + fee` from the receiver.
+pub fn flash_loan(receiver: ContractPackageHash, amount: U256, data: Bytes) {
+    if amount.is_zero() {
+        runtime::revert(WcsprError::ZeroAmount)
+    }
+    if amount > max_flash_loan() {
+        runtime::revert(WcsprError::ExceedFlashLoanLimit)
+    }
+
+    let initiator = get_immediate_caller_address().unwrap_or_revert();
+    let receiver_address = Address::from(receiver);
+    freeze::require_not_frozen(receiver_address);
+
+    let fee = flash_fee(amount);
+    let repayment = amount
+        .checked_add(fee)
+        .unwrap_or_revert_with(WcsprError::AmountOverflow);
+
+    snapshots::before_mint_or_burn(receiver_address);
+    ERC20::default()
+        .mint(receiver_address, amount)
+        .unwrap_or_revert_with(WcsprError::AmountOverflow);
+    votes::on_mint(receiver_address, amount);
+
+    let _: () = runtime::call_versioned_contract(
+        receiver,
+        None,
+        "on_flash_loan",
+        runtime_args! {
+            "initiator" => initiator,
+            "amount" => amount,
+            "fee" => fee,
+            "data" => data,
+        },
+    );
+
+    snapshots::before_mint_or_burn(receiver_address);
+    ERC20::default()
+        .burn(receiver_address, repayment)
+        .unwrap_or_revert_with(WcsprError::FlashLoanNotRepaid);
+    votes::on_burn(receiver_address, repayment);
+
+    // Every WCSPR should be backed by CSPR again
+    reserves::check_invariants();
+
+    emit(WcsprEvent::FlashLoan {
+        receiver: receiver_address,
+        amount,
+        fee,
+    });
+}
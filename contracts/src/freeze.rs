@@ -0,0 +1,42 @@
+//! Frozen addresses for compliance.
+//!
+//! WCSPR of frozen addresses could not be transferred, received, minted or withdrawn until the
+//! address is unfrozen by a freezer.
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::Address;
+
+use crate::error::WcsprError;
+use crate::events::{emit, WcsprEvent};
+use crate::helpers::{address_to_key, get_uref};
+
+pub const FROZEN_DICT_NAME: &str = "frozen";
+
+pub fn init() {
+    storage::new_dictionary(FROZEN_DICT_NAME).unwrap_or_revert();
+}
+
+pub fn is_frozen(address: Address) -> bool {
+    storage::dictionary_get(get_uref(FROZEN_DICT_NAME), &address_to_key(&address))
+        .unwrap_or_revert()
+        .unwrap_or(false)
+}
+
+pub fn freeze(address: Address) {
+    storage::dictionary_put(get_uref(FROZEN_DICT_NAME), &address_to_key(&address), true);
+    emit(WcsprEvent::Frozen { address });
+}
+
+pub fn unfreeze(address: Address) {
+    storage::dictionary_put(get_uref(FROZEN_DICT_NAME), &address_to_key(&address), false);
+    emit(WcsprEvent::Unfrozen { address });
+}
+
+/// Reverts with [`WcsprError::AddressFrozen`] if `address` is frozen.
+pub fn require_not_frozen(address: Address) {
+    if is_frozen(address) {
+        runtime::revert(WcsprError::AddressFrozen)
+    }
+}
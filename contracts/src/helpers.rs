@@ -1,15 +1,21 @@
+use alloc::string::String;
 use casper_contract::{
     contract_api::{runtime, storage},
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_erc20::Address;
 use casper_erc20::Error;
+
+use crate::error::WcsprError;
 use casper_types::bytesrepr::FromBytes;
 use casper_types::bytesrepr::ToBytes;
 use casper_types::CLTyped;
-use casper_types::{system::CallStackElement, Key, URef};
+use casper_types::{system::CallStackElement, Key, URef, U256, U512};
 use core::convert::TryInto;
 
+/// Fees and shares are set in basis points, `BPS_DENOMINATOR` basis points is 100%.
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
 // Helper functions
 
 pub fn set_main_purse(purse: URef) {
@@ -17,11 +23,37 @@ pub fn set_main_purse(purse: URef) {
 }
 
 pub fn get_main_purse() -> URef {
-    let contract_main_purse_key = runtime::get_key("main_purse").unwrap_or_revert();
+    let contract_main_purse_key =
+        runtime::get_key("main_purse").unwrap_or_revert_with(WcsprError::NotInitialized);
     let contract_main_purse = contract_main_purse_key.as_uref().unwrap_or_revert();
     *contract_main_purse
 }
 
+/// Gets [`URef`] stored under `name` key, e.g. a dictionary seed.
+pub fn get_uref(name: &str) -> URef {
+    let key = runtime::get_key(name).unwrap_or_revert_with(WcsprError::NotInitialized);
+    key.into_uref().unwrap_or_revert()
+}
+
+/// Converts CSPR amount into WCSPR amount, reverting on zero or too big amounts.
+pub fn cspr_to_wcspr(cspr_amount: U512) -> U256 {
+    if cspr_amount.is_zero() {
+        runtime::revert(WcsprError::ZeroAmount)
+    }
+    if cspr_amount > U512::from(u128::MAX) {
+        runtime::revert(WcsprError::AmountOverflow)
+    }
+    U256::from(cspr_amount.as_u128())
+}
+
+/// Converts WCSPR amount into CSPR amount, reverting on too big amounts.
+pub fn wcspr_to_cspr(amount: U256) -> U512 {
+    if amount > U256::from(u128::MAX) {
+        runtime::revert(WcsprError::AmountOverflow)
+    }
+    U512::from(amount.as_u128())
+}
+
 pub fn get_key<T: FromBytes + CLTyped>(name: &str) -> Option<T> {
     match runtime::get_key(name) {
         None => None,
@@ -46,6 +78,12 @@ pub fn set_key<T: ToBytes + CLTyped>(name: &str, value: T) {
     }
 }
 
+/// Makes dictionary item key for `address`.
+pub fn address_to_key(address: &Address) -> String {
+    let preimage = address.to_bytes().unwrap_or_revert();
+    base64::encode(&preimage)
+}
+
 /// Gets the immediate call stack element of the current execution.
 pub fn get_immediate_call_stack_item() -> Option<CallStackElement> {
     let call_stack = runtime::get_call_stack();
@@ -79,4 +117,4 @@ pub(crate) fn get_immediate_caller_address() -> Result<Address, Error> {
     get_immediate_call_stack_item()
         .map(call_stack_element_to_address)
         .ok_or(Error::InvalidContext)
-}
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_erc20::{Address, ERC20};
+use casper_types::U256;
+
+use crate::error::WcsprError;
+use crate::helpers::{get_key, set_key};
+
+pub const USER_LIMIT_KEY_NAME: &str = "user_limit";
+pub const CONTRACT_LIMIT_KEY_NAME: &str = "contract_limit";
+
+pub fn set_limits(user_limit: U256, contract_limit: U256) {
+    set_key(USER_LIMIT_KEY_NAME, user_limit);
+    set_key(CONTRACT_LIMIT_KEY_NAME, contract_limit);
+}
+
+/// Returns `(user_limit, contract_limit)` stored in the contract.
+pub fn get_limits() -> (U256, U256) {
+    let user_limit: U256 = get_key(USER_LIMIT_KEY_NAME).unwrap_or_revert();
+    let contract_limit: U256 = get_key(CONTRACT_LIMIT_KEY_NAME).unwrap_or_revert();
+    (user_limit, contract_limit)
+}
+
+/// Returns how much WCSPR could still be minted to `owner` before hitting either limit.
+pub fn remaining_quota(owner: Address) -> U256 {
+    let (user_limit, contract_limit) = get_limits();
+    let erc20 = ERC20::default();
+
+    let user_quota = user_limit.saturating_sub(erc20.balance_of(owner));
+    let contract_quota = contract_limit.saturating_sub(erc20.total_supply());
+    user_quota.min(contract_quota)
+}
+
+/// Reverts if minting `amount` WCSPR to `owner` would exceed the contract or user limit.
+pub fn check_deposit_limits(owner: Address, amount: U256) {
+    let (user_limit, contract_limit) = get_limits();
+    let erc20 = ERC20::default();
+
+    // Stop deposit if total supply exceed contract limits
+    if erc20.total_supply() + amount > contract_limit {
+        runtime::revert(WcsprError::ExceedContractLimit)
+    }
+
+    // Stop deposit if user exceeds personal limits
+    if erc20.balance_of(owner) + amount > user_limit {
+        runtime::revert(WcsprError::ExceedUserLimit)
+    }
+}
@@ -0,0 +1,90 @@
+//! Versioned storage layout of the contract.
+//!
+//! `init` creates the storage of the current [`STORAGE_VERSION`]. Contracts upgraded from an older
+//! version run `migrate`, that applies every layout change made since the stored version.
+use alloc::string::String;
+
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+
+use crate::error::WcsprError;
+use crate::helpers::{get_immediate_caller_address, get_key, set_key};
+use crate::pause::{self, Operation};
+use crate::roles::{self, Role};
+use crate::{
+    allowlist, circuit_breaker, events, fees, flash_loan, freeze, permit, self_transfer, snapshots,
+    votes,
+};
+
+pub const STORAGE_VERSION_KEY_NAME: &str = "storage_version";
+
+/// Version of the storage layout created by this contract version.
+pub const STORAGE_VERSION: u32 = 10;
+
+pub fn set_storage_version(version: u32) {
+    set_key(STORAGE_VERSION_KEY_NAME, version);
+}
+
+/// Brings storage created by an older contract version up to [`STORAGE_VERSION`].
+pub fn migrate() {
+    let version: u32 =
+        get_key(STORAGE_VERSION_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized);
+    if version > STORAGE_VERSION {
+        runtime::revert(WcsprError::UnsupportedStorageVersion)
+    }
+
+    // 2: flash loans
+    if version < 2 {
+        flash_loan::init();
+        pause::set_paused(Operation::FlashLoan, false);
+        events::set_schema();
+    }
+
+    // 3: permits, chain name is passed by the upgrade session code
+    if version < 3 {
+        let chain_name: String = runtime::get_named_arg("chain_name");
+        permit::init(chain_name);
+    }
+
+    // 4: transfers to the contract itself, upgraded contracts revert them
+    if version < 4 {
+        self_transfer::init(false);
+    }
+
+    // 5: deposit and withdraw fees, admin running the upgrade is the initial treasury
+    if version < 5 {
+        let admin = get_immediate_caller_address().unwrap_or_revert();
+        fees::init(*admin.as_account_hash().unwrap_or_revert());
+        events::set_schema();
+    }
+
+    // 6: balance snapshots
+    if version < 6 {
+        snapshots::init();
+    }
+
+    // 7: vote delegation
+    if version < 7 {
+        votes::init();
+    }
+
+    // 8: frozen addresses, admin running the upgrade is the initial freezer
+    if version < 8 {
+        let admin = get_immediate_caller_address().unwrap_or_revert();
+        roles::add_role(Role::Freezer, admin);
+        freeze::init();
+        events::set_schema();
+    }
+
+    // 9: withdrawal circuit breaker
+    if version < 9 {
+        circuit_breaker::init();
+        events::set_schema();
+    }
+
+    // 10: allowlist tiers
+    if version < 10 {
+        allowlist::init();
+    }
+
+    set_storage_version(STORAGE_VERSION);
+}
@@ -0,0 +1,72 @@
+//! Per operation pause switches.
+//!
+//! Every operation has its own `<operation>_paused` named key, so e.g. deposits could be paused
+//! while withdrawals stay open.
+use casper_contract::contract_api::runtime;
+
+use crate::error::WcsprError;
+use crate::helpers::{get_key, set_key};
+
+#[derive(Clone, Copy)]
+pub enum Operation {
+    Deposit,
+    Withdraw,
+    /// Both `transfer` and `transfer_from`.
+    Transfer,
+    Approve,
+    FlashLoan,
+}
+
+const ALL_OPERATIONS: [Operation; 5] = [
+    Operation::Deposit,
+    Operation::Withdraw,
+    Operation::Transfer,
+    Operation::Approve,
+    Operation::FlashLoan,
+];
+
+impl Operation {
+    /// Parses operation name passed as a runtime argument, reverting on unknown names.
+    pub fn from_name(name: &str) -> Operation {
+        match name {
+            "deposit" => Operation::Deposit,
+            "withdraw" => Operation::Withdraw,
+            "transfer" => Operation::Transfer,
+            "approve" => Operation::Approve,
+            "flash_loan" => Operation::FlashLoan,
+            _ => runtime::revert(WcsprError::UnknownOperation),
+        }
+    }
+
+    fn key_name(self) -> &'static str {
+        match self {
+            Operation::Deposit => "deposit_paused",
+            Operation::Withdraw => "withdraw_paused",
+            Operation::Transfer => "transfer_paused",
+            Operation::Approve => "approve_paused",
+            Operation::FlashLoan => "flash_loan_paused",
+        }
+    }
+}
+
+/// Creates unpaused switches for every operation.
+pub fn init() {
+    for operation in ALL_OPERATIONS.iter() {
+        set_paused(*operation, false);
+    }
+}
+
+pub fn set_paused(operation: Operation, paused: bool) {
+    set_key(operation.key_name(), paused);
+}
+
+pub fn is_paused(operation: Operation) -> bool {
+    get_key(operation.key_name()).unwrap_or(false)
+}
+
+/// Reverts with [`WcsprError::ContractPaused`] if `operation` is paused.
+pub fn require_not_paused(operation: Operation) {
+    if is_paused(operation) {
+        runtime::revert(WcsprError::ContractPaused)
+    }
+}
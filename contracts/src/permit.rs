@@ -0,0 +1,109 @@
+//! Approvals signed off-chain with Casper public keys.
+//!
+//! Owner signs blake2b digest of the domain separator followed by `owner`, `spender`, `amount`,
+//! `nonce` and `deadline`, every value serialized with `bytesrepr`. Domain separator is blake2b
+//! digest of `"WCSPR permit"`, chain name and hash of the contract.
+use alloc::{string::String, vec::Vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::Address;
+use casper_types::{
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    crypto,
+    system::CallStackElement,
+    ContractHash, PublicKey, Signature, U256,
+};
+
+use crate::allowances::write_allowance;
+use crate::error::WcsprError;
+use crate::events::{emit, WcsprEvent};
+use crate::helpers::{address_to_key, get_key, get_uref, set_key};
+
+pub const NONCES_DICT_NAME: &str = "nonces";
+pub const CHAIN_NAME_KEY_NAME: &str = "chain_name";
+
+const PERMIT_DOMAIN: &str = "WCSPR permit";
+
+/// Creates nonces dictionary and saves chain name used in the domain separator.
+pub fn init(chain_name: String) {
+    storage::new_dictionary(NONCES_DICT_NAME).unwrap_or_revert();
+    set_key(CHAIN_NAME_KEY_NAME, chain_name);
+}
+
+/// Returns the next permit nonce of `owner`.
+pub fn nonce(owner: Address) -> u64 {
+    storage::dictionary_get(get_uref(NONCES_DICT_NAME), &address_to_key(&owner))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn current_contract_hash() -> ContractHash {
+    match runtime::get_call_stack().last() {
+        Some(CallStackElement::StoredContract { contract_hash, .. }) => *contract_hash,
+        _ => runtime::revert(WcsprError::NotInitialized),
+    }
+}
+
+fn domain_separator() -> [u8; 32] {
+    let chain_name: String =
+        get_key(CHAIN_NAME_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized);
+
+    let mut preimage = Vec::new();
+    preimage.append(&mut String::from(PERMIT_DOMAIN).to_bytes().unwrap_or_revert());
+    preimage.append(&mut chain_name.to_bytes().unwrap_or_revert());
+    preimage.append(&mut current_contract_hash().to_bytes().unwrap_or_revert());
+    runtime::blake2b(&preimage)
+}
+
+fn permit_digest(
+    owner: &PublicKey,
+    spender: Address,
+    amount: U256,
+    nonce: u64,
+    deadline: u64,
+) -> [u8; 32] {
+    let mut preimage = Vec::new();
+    preimage.extend_from_slice(&domain_separator());
+    preimage.append(&mut owner.to_bytes().unwrap_or_revert());
+    preimage.append(&mut spender.to_bytes().unwrap_or_revert());
+    preimage.append(&mut amount.to_bytes().unwrap_or_revert());
+    preimage.append(&mut nonce.to_bytes().unwrap_or_revert());
+    preimage.append(&mut deadline.to_bytes().unwrap_or_revert());
+    runtime::blake2b(&preimage)
+}
+
+/// Sets allowance of `spender` over `owner` tokens if `signature` of the permit is valid.
+pub fn permit(owner: PublicKey, spender: Address, amount: U256, deadline: u64, signature: Bytes) {
+    if u64::from(runtime::get_blocktime()) > deadline {
+        runtime::revert(WcsprError::PermitExpired)
+    }
+
+    let owner_address = Address::from(owner.to_account_hash());
+    let nonce = nonce(owner_address);
+    let digest = permit_digest(&owner, spender, amount, nonce, deadline);
+
+    // Signature should be exactly one serialized signature, without any trailing bytes
+    let (signature, remainder) =
+        Signature::from_bytes(&signature).unwrap_or_revert_with(WcsprError::InvalidSignature);
+    if !remainder.is_empty() || crypto::verify(&digest, &signature, &owner).is_err() {
+        runtime::revert(WcsprError::InvalidSignature)
+    }
+
+    // Every signed permit could be used only once
+    storage::dictionary_put(
+        get_uref(NONCES_DICT_NAME),
+        &address_to_key(&owner_address),
+        nonce + 1,
+    );
+
+    write_allowance(owner_address, spender, amount);
+
+    emit(WcsprEvent::Approval {
+        owner: owner_address,
+        spender,
+        amount,
+    });
+}
@@ -4,23 +4,13 @@ use casper_contract::{
     contract_api::{account, runtime, system},
     unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_erc20::Address;
 /*
+use casper_erc20::Address;
 use casper_erc20::Error;
 use casper_types::system::CallStackElement;
 */
 use casper_types::RuntimeArgs;
-use casper_types::{runtime_args, ApiError, ContractHash, HashAddr, Key, URef, U256, U512};
-pub enum DepositError {
-    ExceedUserLimit = 0,
-    ExceedContractLimit = 1,
-}
-
-impl From<DepositError> for ApiError {
-    fn from(error: DepositError) -> ApiError {
-        ApiError::User(error as u16)
-    }
-}
+use casper_types::{runtime_args, ContractHash, HashAddr, Key, URef, U512};
 
 #[no_mangle]
 fn call() {
@@ -30,46 +20,13 @@ fn call() {
 
     // how many cspr tokens to transfer
     let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
-    let cspr_amount_u256: U256 = U256::from(cspr_amount.as_u128());
 
     // WCSPR contract hash address passed as an argument to this contract
     let wcspr_contract_key: Key = runtime::get_named_arg("wcspr_contract_hash_key");
     let _wcspr_contract_hash: HashAddr = wcspr_contract_key.into_hash().unwrap_or_revert();
     let wcspr_contract_hash: ContractHash = ContractHash::new(_wcspr_contract_hash);
 
-    // Get total WCSPR supply
-    let total_supply: U256 =
-        runtime::call_contract::<U256>(wcspr_contract_hash, "total_supply", runtime_args! {});
-
-    // Stop deposit if total supply exceed contract limits
-    // 200 CSPR (200 * 10**9)
-    // let multiplier: U256 = U256:from(10.pow(9));
-    let multiplier: U256 = (U256::from(10)).pow(U256::from(9));
-    let contract_limit: U256 = U256::from(200) * multiplier;
-
-    if (total_supply + cspr_amount_u256) > contract_limit {
-        runtime::revert(DepositError::ExceedContractLimit)
-    };
-
-    // Get Address (AccountHash) of the user who called the contract
-    let sender: Address = Address::from(runtime::get_caller());
-
-    // Get user's WCSPR balance
-    let sender_wcspr_balance: U256 = runtime::call_contract::<U256>(
-        wcspr_contract_hash,
-        "balance_of",
-        runtime_args! {
-            "address" => sender
-        },
-    );
-
-    // Stop deposit if user exceeds personal limits
-    // 100 CSPR (100 * 10**9)
-    let user_limit: U256 = U256::from(100) * multiplier;
-
-    if (sender_wcspr_balance + cspr_amount_u256) > user_limit {
-        runtime::revert(DepositError::ExceedUserLimit)
-    };
+    // User and contract deposit limits are enforced by the `deposit` entry point itself
 
     // Purse with CSPR tokens of the user who call the contract
     let sender_purse: URef = account::get_main_purse();
@@ -78,7 +35,8 @@ fn call() {
     let tmp_purse: URef = system::create_purse();
 
     // move from sender to tmp purse, so we can use tmp purse in the next contract
-    let _ = system::transfer_from_purse_to_purse(sender_purse, tmp_purse, cspr_amount, None);
+    system::transfer_from_purse_to_purse(sender_purse, tmp_purse, cspr_amount, None)
+        .unwrap_or_revert();
 
     runtime::call_contract(
         wcspr_contract_hash,
@@ -0,0 +1,31 @@
+//! CSPR reserves backing WCSPR supply.
+//!
+//! Every WCSPR should be backed by CSPR in the contract main purse. Main purse could hold more
+//! CSPR than the total supply, e.g. paid flash loan fees or CSPR sent to the purse directly.
+use casper_contract::{
+    contract_api::{runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::ERC20;
+use casper_types::U512;
+
+use crate::error::WcsprError;
+use crate::helpers::{get_main_purse, wcspr_to_cspr};
+
+/// Returns CSPR balance of the contract main purse.
+pub fn reserves() -> U512 {
+    system::get_purse_balance(get_main_purse()).unwrap_or_revert()
+}
+
+/// Reverts if total supply of WCSPR exceeds CSPR reserves.
+pub fn check_invariants() {
+    if wcspr_to_cspr(ERC20::default().total_supply()) > reserves() {
+        runtime::revert(WcsprError::InvariantViolated)
+    }
+}
+
+/// Returns CSPR reserves not backing any WCSPR.
+pub fn excess_reserves() -> U512 {
+    check_invariants();
+    reserves() - wcspr_to_cspr(ERC20::default().total_supply())
+}
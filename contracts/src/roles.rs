@@ -0,0 +1,96 @@
+//! Role based access control.
+//!
+//! Every role has its own dictionary of [`Address`]es holding it. Admins grant and revoke roles,
+//! other roles gate admin entry points of the contract.
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::Address;
+
+use crate::error::WcsprError;
+use crate::helpers::{address_to_key, get_immediate_caller_address, get_uref};
+
+#[derive(Clone, Copy)]
+pub enum Role {
+    /// Grants and revokes roles.
+    Admin,
+    /// Pauses and unpauses contract operations.
+    Pauser,
+    /// Changes deposit limits.
+    LimitsManager,
+    /// Freezes and unfreezes addresses.
+    Freezer,
+}
+
+const ALL_ROLES: [Role; 4] = [
+    Role::Admin,
+    Role::Pauser,
+    Role::LimitsManager,
+    Role::Freezer,
+];
+
+impl Role {
+    /// Parses role name passed as a runtime argument, reverting on unknown names.
+    pub fn from_name(name: &str) -> Role {
+        match name {
+            "admin" => Role::Admin,
+            "pauser" => Role::Pauser,
+            "limits_manager" => Role::LimitsManager,
+            "freezer" => Role::Freezer,
+            _ => runtime::revert(WcsprError::UnknownRole),
+        }
+    }
+
+    fn dictionary_name(self) -> &'static str {
+        match self {
+            Role::Admin => "admin_role",
+            Role::Pauser => "pauser_role",
+            Role::LimitsManager => "limits_manager_role",
+            Role::Freezer => "freezer_role",
+        }
+    }
+}
+
+/// Creates role dictionaries and grants every role to `admin`.
+pub fn init(admin: Address) {
+    for role in ALL_ROLES.iter() {
+        add_role(*role, admin);
+    }
+}
+
+/// Creates dictionary of a new `role` and grants it to `admin`.
+pub fn add_role(role: Role, admin: Address) {
+    storage::new_dictionary(role.dictionary_name()).unwrap_or_revert();
+    grant_role(role, admin);
+}
+
+pub fn has_role(role: Role, address: Address) -> bool {
+    storage::dictionary_get(get_uref(role.dictionary_name()), &address_to_key(&address))
+        .unwrap_or_revert()
+        .unwrap_or(false)
+}
+
+pub fn grant_role(role: Role, address: Address) {
+    storage::dictionary_put(
+        get_uref(role.dictionary_name()),
+        &address_to_key(&address),
+        true,
+    );
+}
+
+pub fn revoke_role(role: Role, address: Address) {
+    storage::dictionary_put(
+        get_uref(role.dictionary_name()),
+        &address_to_key(&address),
+        false,
+    );
+}
+
+/// Reverts with [`WcsprError::MissingRole`] unless the immediate caller holds `role`.
+pub fn require_role(role: Role) {
+    let caller = get_immediate_caller_address().unwrap_or_revert();
+    if !has_role(role, caller) {
+        runtime::revert(WcsprError::MissingRole)
+    }
+}
@@ -0,0 +1,33 @@
+//! Handling of WCSPR sent to the WCSPR contract package itself.
+//!
+//! Such tokens could never be moved again, so transfers to the contract are either reverted or,
+//! like WETH does, unwrapped into CSPR paid to the account of the token owner. Behaviour is chosen
+//! on install with `unwrap_self_transfers` argument.
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_erc20::Address;
+use casper_types::system::CallStackElement;
+
+use crate::error::WcsprError;
+use crate::helpers::{get_key, set_key};
+
+pub const UNWRAP_SELF_TRANSFERS_KEY_NAME: &str = "unwrap_self_transfers";
+
+pub fn init(unwrap_self_transfers: bool) {
+    set_key(UNWRAP_SELF_TRANSFERS_KEY_NAME, unwrap_self_transfers);
+}
+
+/// Returns `true` if WCSPR sent to the contract should be unwrapped instead of reverting.
+pub fn unwraps_self_transfers() -> bool {
+    get_key(UNWRAP_SELF_TRANSFERS_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized)
+}
+
+/// Returns `true` if `recipient` is the package of the current contract.
+pub fn is_self(recipient: Address) -> bool {
+    match runtime::get_call_stack().last() {
+        Some(CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        }) => recipient == Address::from(*contract_package_hash),
+        _ => false,
+    }
+}
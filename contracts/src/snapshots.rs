@@ -0,0 +1,126 @@
+//! ERC20Snapshot style balance snapshots.
+//!
+//! Snapshot increases the current snapshot id and saves total supply under the new id. Balances
+//! are saved lazily: the first time a balance changes after a snapshot, the value before the change
+//! is appended to the history of that account together with the current snapshot id.
+use alloc::{string::ToString, vec::Vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::{Address, ERC20};
+use casper_types::U256;
+
+use crate::error::WcsprError;
+use crate::helpers::{address_to_key, get_key, get_uref, set_key};
+
+pub const CURRENT_SNAPSHOT_ID_KEY_NAME: &str = "current_snapshot_id";
+pub const ACCOUNT_SNAPSHOTS_DICT_NAME: &str = "account_snapshots";
+pub const TOTAL_SUPPLY_SNAPSHOTS_DICT_NAME: &str = "total_supply_snapshots";
+
+/// Values saved before the first change after each snapshot, as `(snapshot_id, value)` pairs in
+/// increasing snapshot id order.
+type Snapshots = Vec<(u64, U256)>;
+
+pub fn init() {
+    storage::new_dictionary(ACCOUNT_SNAPSHOTS_DICT_NAME).unwrap_or_revert();
+    storage::new_dictionary(TOTAL_SUPPLY_SNAPSHOTS_DICT_NAME).unwrap_or_revert();
+    set_key(CURRENT_SNAPSHOT_ID_KEY_NAME, 0u64);
+}
+
+fn current_snapshot_id() -> u64 {
+    get_key(CURRENT_SNAPSHOT_ID_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized)
+}
+
+/// Takes a new snapshot and returns its id, ids start from 1.
+pub fn snapshot() -> u64 {
+    let snapshot_id = current_snapshot_id() + 1;
+    set_key(CURRENT_SNAPSHOT_ID_KEY_NAME, snapshot_id);
+    storage::dictionary_put(
+        get_uref(TOTAL_SUPPLY_SNAPSHOTS_DICT_NAME),
+        &snapshot_id.to_string(),
+        ERC20::default().total_supply(),
+    );
+    snapshot_id
+}
+
+fn account_snapshots(owner: Address) -> Snapshots {
+    storage::dictionary_get(
+        get_uref(ACCOUNT_SNAPSHOTS_DICT_NAME),
+        &address_to_key(&owner),
+    )
+    .unwrap_or_revert()
+    .unwrap_or_default()
+}
+
+/// Appends `current_value` to `snapshots` if it was not saved since the current snapshot yet.
+fn update_snapshots(snapshots: &mut Snapshots, current_value: U256) -> bool {
+    let snapshot_id = current_snapshot_id();
+    let last_snapshot_id = snapshots.last().map(|(id, _)| *id).unwrap_or_default();
+    if last_snapshot_id < snapshot_id {
+        snapshots.push((snapshot_id, current_value));
+        true
+    } else {
+        false
+    }
+}
+
+fn update_account(owner: Address) {
+    let mut snapshots = account_snapshots(owner);
+    if update_snapshots(&mut snapshots, ERC20::default().balance_of(owner)) {
+        storage::dictionary_put(
+            get_uref(ACCOUNT_SNAPSHOTS_DICT_NAME),
+            &address_to_key(&owner),
+            snapshots,
+        );
+    }
+}
+
+/// Should be called before WCSPR are moved from `sender` to `recipient`.
+pub fn before_transfer(sender: Address, recipient: Address) {
+    if current_snapshot_id() == 0 {
+        return;
+    }
+    update_account(sender);
+    update_account(recipient);
+}
+
+/// Should be called before WCSPR are minted to or burned from `owner`.
+pub fn before_mint_or_burn(owner: Address) {
+    if current_snapshot_id() == 0 {
+        return;
+    }
+    update_account(owner);
+}
+
+fn require_snapshot(snapshot_id: u64) {
+    if snapshot_id == 0 || snapshot_id > current_snapshot_id() {
+        runtime::revert(WcsprError::SnapshotNotFound)
+    }
+}
+
+/// Returns value saved for `snapshot_id`, or `None` if the value was not changed since.
+fn value_at(snapshots: &[(u64, U256)], snapshot_id: u64) -> Option<U256> {
+    require_snapshot(snapshot_id);
+    // First value saved at or after the requested snapshot is the value at the snapshot
+    snapshots
+        .iter()
+        .find(|(id, _)| *id >= snapshot_id)
+        .map(|(_, value)| *value)
+}
+
+pub fn balance_of_at(owner: Address, snapshot_id: u64) -> U256 {
+    value_at(&account_snapshots(owner), snapshot_id)
+        .unwrap_or_else(|| ERC20::default().balance_of(owner))
+}
+
+pub fn total_supply_at(snapshot_id: u64) -> U256 {
+    require_snapshot(snapshot_id);
+    storage::dictionary_get(
+        get_uref(TOTAL_SUPPLY_SNAPSHOTS_DICT_NAME),
+        &snapshot_id.to_string(),
+    )
+    .unwrap_or_revert()
+    .unwrap_or_revert_with(WcsprError::SnapshotNotFound)
+}
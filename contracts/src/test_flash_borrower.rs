@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+
+#[cfg(not(target_arch = "wasm32"))]
+compile_error!("target arch should be wasm32: compile with '--target wasm32-unknown-unknown'");
+
+// Helper contract used by integration tests only.
+// It receives WCSPR flash loans and keeps the borrowed amount to repay it.
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_erc20::Address;
+use casper_types::{
+    bytesrepr::Bytes, contracts::NamedKeys, CLType, CLTyped, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Key, Parameter, U256,
+};
+
+const CONTRACT_KEY_NAME: &str = "test_flash_borrower";
+const CONTRACT_PACKAGE_KEY_NAME: &str = "test_flash_borrower_package_hash";
+
+#[no_mangle]
+pub extern "C" fn on_flash_loan() {
+    let _initiator: Address = runtime::get_named_arg("initiator");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let _fee: U256 = runtime::get_named_arg("fee");
+    let _data: Bytes = runtime::get_named_arg("data");
+
+    // Save borrowed amount, so tests could check the callback was called
+    runtime::put_key("last_loan_amount", Key::from(storage::new_uref(amount)));
+}
+
+#[no_mangle]
+fn call() {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("on_flash_loan"),
+        vec![
+            Parameter::new("initiator", Address::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("fee", U256::cl_type()),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(NamedKeys::new()),
+        Some(String::from(CONTRACT_PACKAGE_KEY_NAME)),
+        None,
+    );
+    runtime::put_key(CONTRACT_KEY_NAME, Key::from(contract_hash));
+}
@@ -0,0 +1,43 @@
+#![no_main]
+
+// Helper session code used by integration tests only.
+// It calls a read-only `entry_point` of the WCSPR contract with `args` (serialized `RuntimeArgs`)
+// and saves the returned `result_type` value (U256 or U512) to the `query_result` named key of the
+// caller, so tests read values computed by the contract itself.
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{Bytes, FromBytes},
+    ApiError, ContractHash, HashAddr, Key, RuntimeArgs, U256, U512,
+};
+
+#[no_mangle]
+fn call() {
+    let entry_point: String = runtime::get_named_arg("entry_point");
+    let result_type: String = runtime::get_named_arg("result_type");
+    let args: Bytes = runtime::get_named_arg("args");
+    let (args, _) = RuntimeArgs::from_bytes(&args).unwrap_or_revert();
+
+    // WCSPR contract hash address passed as an argument to this contract
+    let wcspr_contract_key: Key = runtime::get_named_arg("wcspr_contract_hash_key");
+    let _wcspr_contract_hash: HashAddr = wcspr_contract_key.into_hash().unwrap_or_revert();
+    let wcspr_contract_hash: ContractHash = ContractHash::new(_wcspr_contract_hash);
+
+    let result = match result_type.as_str() {
+        "U256" => storage::new_uref(runtime::call_contract::<U256>(
+            wcspr_contract_hash,
+            &entry_point,
+            args,
+        )),
+        "U512" => storage::new_uref(runtime::call_contract::<U512>(
+            wcspr_contract_hash,
+            &entry_point,
+            args,
+        )),
+        _ => runtime::revert(ApiError::InvalidArgument),
+    };
+    runtime::put_key("query_result", Key::from(result));
+}
@@ -0,0 +1,76 @@
+#![no_std]
+#![no_main]
+
+#[cfg(not(target_arch = "wasm32"))]
+compile_error!("target arch should be wasm32: compile with '--target wasm32-unknown-unknown'");
+
+// Helper contract used by integration tests only.
+// It holds WCSPR on its package balance and unwraps them into its own purse.
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+
+use casper_contract::{
+    contract_api::{runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, runtime_args, CLType, CLTyped, ContractHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, URef, U512,
+};
+
+const CONTRACT_KEY_NAME: &str = "test_vault";
+const CONTRACT_PACKAGE_KEY_NAME: &str = "test_vault_package_hash";
+
+#[no_mangle]
+pub extern "C" fn unwrap() {
+    let wcspr_contract_key: Key = runtime::get_named_arg("wcspr_contract_hash_key");
+    let wcspr_contract_hash = ContractHash::new(wcspr_contract_key.into_hash().unwrap_or_revert());
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+
+    let purse: URef = *runtime::get_key("purse")
+        .unwrap_or_revert()
+        .as_uref()
+        .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(
+        wcspr_contract_hash,
+        "withdraw_to_purse",
+        runtime_args! {
+            // WCSPR contract only deposits into the purse
+            "purse" => purse.into_add(),
+            "cspr_amount" => cspr_amount,
+        },
+    );
+
+    // Save cspr balance of the vault purse, so tests could check it
+    let cspr_balance = system::get_purse_balance(purse).unwrap_or_revert();
+    runtime::put_key("cspr_balance", Key::from(storage::new_uref(cspr_balance)));
+}
+
+#[no_mangle]
+fn call() {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("unwrap"),
+        vec![
+            Parameter::new("wcspr_contract_hash_key", Key::cl_type()),
+            Parameter::new("cspr_amount", U512::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(String::from("purse"), Key::from(system::create_purse()));
+
+    let (contract_hash, _) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(String::from(CONTRACT_PACKAGE_KEY_NAME)),
+        None,
+    );
+    runtime::put_key(CONTRACT_KEY_NAME, Key::from(contract_hash));
+}
@@ -0,0 +1,153 @@
+#![no_std]
+#![no_main]
+
+#[cfg(not(target_arch = "wasm32"))]
+compile_error!("target arch should be wasm32: compile with '--target wasm32-unknown-unknown'");
+
+// Helper contract used by integration tests only.
+// It installs the WCSPR package with storage layout of version 1, the first versioned release,
+// and `cspr_amount` of WCSPR held by the installer. Tests upgrade it to check storage migrations.
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+};
+
+use casper_contract::{
+    contract_api::{account, runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::{
+    constants::{
+        ALLOWANCES_KEY_NAME, BALANCES_KEY_NAME, DECIMALS_KEY_NAME, NAME_KEY_NAME, SYMBOL_KEY_NAME,
+        TOTAL_SUPPLY_KEY_NAME,
+    },
+    Address,
+};
+use casper_types::{
+    bytesrepr::ToBytes, contracts::NamedKeys, ApiError, CLTyped, CLValue, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, URef, U256, U512,
+};
+
+const CONTRACT_KEY_NAME: &str = "wcspr_token";
+const CONTRACT_PACKAGE_KEY_NAME: &str = "wcspr_token_package_hash";
+const CONTRACT_ACCESS_KEY_NAME: &str = "wcspr_token_package_access";
+const PACKAGE_ACCESS_KEY_NAME: &str = "package_access";
+
+#[no_mangle]
+pub extern "C" fn named_keys_for_upgrade() {
+    let package_access: URef = runtime::get_named_arg("package_access");
+
+    let package_access_key = runtime::get_key(PACKAGE_ACCESS_KEY_NAME)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    let package_access_addr: [u8; 32] = storage::read(package_access_key)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if package_access.addr() != package_access_addr {
+        runtime::revert(ApiError::PermissionDenied)
+    }
+
+    runtime::ret(CLValue::from_t(runtime::list_named_keys()).unwrap_or_revert());
+}
+
+fn address_to_key(address: Address) -> String {
+    base64::encode(&address.to_bytes().unwrap_or_revert())
+}
+
+fn new_key<T: CLTyped + ToBytes>(value: T) -> Key {
+    Key::from(storage::new_uref(value))
+}
+
+/// Creates dictionary seed URef, the same as `storage::new_dictionary` does, without putting it
+/// to the installer named keys.
+fn new_dictionary(named_keys: &mut NamedKeys, name: &str) -> URef {
+    let seed = storage::new_uref(());
+    named_keys.insert(name.to_string(), Key::from(seed));
+    seed
+}
+
+#[no_mangle]
+fn call() {
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+    let user_limit: U256 = runtime::get_named_arg("user_limit");
+    let contract_limit: U256 = runtime::get_named_arg("contract_limit");
+
+    let installer = Address::from(runtime::get_caller());
+    let amount = U256::from(cspr_amount.as_u128());
+
+    let mut named_keys = NamedKeys::new();
+
+    // ERC20 state with `amount` balanced to the installer
+    named_keys.insert(
+        NAME_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(String::from("Wrapped Casper")).into_read()),
+    );
+    named_keys.insert(
+        SYMBOL_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(String::from("WCSPR")).into_read()),
+    );
+    named_keys.insert(
+        DECIMALS_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(9u8).into_read()),
+    );
+    named_keys.insert(TOTAL_SUPPLY_KEY_NAME.to_string(), new_key(amount));
+    let balances = new_dictionary(&mut named_keys, BALANCES_KEY_NAME);
+    storage::dictionary_put(balances, &address_to_key(installer), amount);
+    new_dictionary(&mut named_keys, ALLOWANCES_KEY_NAME);
+
+    // CSPR backing the installer balance
+    let main_purse = system::create_purse();
+    system::transfer_from_purse_to_purse(account::get_main_purse(), main_purse, cspr_amount, None)
+        .unwrap_or_revert();
+    named_keys.insert("main_purse".to_string(), Key::from(main_purse));
+    named_keys.insert("cspr_balance".to_string(), new_key(cspr_amount));
+
+    // Roles of version 1, all of them held by the installer
+    for role in ["admin", "pauser", "limits_manager"].iter() {
+        let role_dictionary = new_dictionary(&mut named_keys, &format!("{}_role", role));
+        storage::dictionary_put(role_dictionary, &address_to_key(installer), true);
+    }
+
+    // Pause switches of version 1
+    for operation in ["deposit", "withdraw", "transfer", "approve"].iter() {
+        named_keys.insert(format!("{}_paused", operation), new_key(false));
+    }
+
+    named_keys.insert("user_limit".to_string(), new_key(user_limit));
+    named_keys.insert("contract_limit".to_string(), new_key(contract_limit));
+
+    new_dictionary(&mut named_keys, "__events");
+    named_keys.insert("__events_length".to_string(), new_key(0u32));
+    named_keys.insert(
+        "__events_ces_version".to_string(),
+        new_key(String::from("0.1.0")),
+    );
+
+    named_keys.insert("storage_version".to_string(), new_key(1u32));
+    named_keys.insert("initialized".to_string(), new_key(true));
+
+    let (package_hash, package_access) = storage::create_contract_package_at_hash();
+    named_keys.insert(
+        PACKAGE_ACCESS_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(package_access.addr()).into_read()),
+    );
+
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        String::from("named_keys_for_upgrade"),
+        vec![Parameter::new("package_access", URef::cl_type())],
+        NamedKeys::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let (contract_hash, _) = storage::add_contract_version(package_hash, entry_points, named_keys);
+    runtime::put_key(CONTRACT_PACKAGE_KEY_NAME, Key::from(package_hash));
+    runtime::put_key(CONTRACT_ACCESS_KEY_NAME, Key::from(package_access));
+    runtime::put_key(CONTRACT_KEY_NAME, Key::from(contract_hash));
+}
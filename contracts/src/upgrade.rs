@@ -0,0 +1,71 @@
+//! Upgrades of the contract package.
+//!
+//! The installer keeps the package access URef. Running the install session code again adds a new
+//! contract version, that takes over named keys of the previous version and migrates them.
+use alloc::string::String;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    contracts::NamedKeys, runtime_args, ContractHash, ContractPackageHash, RuntimeArgs, URef,
+};
+
+use crate::entry_points;
+use crate::error::WcsprError;
+use crate::helpers::get_key;
+
+/// Address of the package access URef, saved by the installer.
+pub const PACKAGE_ACCESS_KEY_NAME: &str = "package_access";
+
+/// Returns named keys of the contract, reverting unless `package_access` is the package access
+/// URef.
+///
+/// URefs passed to a contract are checked by the execution engine, so only the holder of the
+/// package access URef could get write access to the contract storage.
+pub fn named_keys(package_access: URef) -> NamedKeys {
+    let package_access_addr: [u8; 32] =
+        get_key(PACKAGE_ACCESS_KEY_NAME).unwrap_or_revert_with(WcsprError::NotInitialized);
+    if package_access.addr() != package_access_addr {
+        runtime::revert(WcsprError::InvalidPackageAccess)
+    }
+    runtime::list_named_keys()
+}
+
+/// Adds a new contract version to the package and returns its hash, called by the installer
+/// session code.
+///
+/// New version keeps named keys of `previous_contract_hash`, so `main_purse`, `balances`,
+/// `allowances` and the rest of the state stay in place. Previous version is disabled, then
+/// `migrate` brings the storage up to the layout of the new version.
+pub fn upgrade(
+    package_hash: ContractPackageHash,
+    package_access: URef,
+    previous_contract_hash: ContractHash,
+) -> ContractHash {
+    let named_keys: NamedKeys = runtime::call_contract(
+        previous_contract_hash,
+        "named_keys_for_upgrade",
+        runtime_args! {
+            "package_access" => package_access,
+        },
+    );
+
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, entry_points::default(), named_keys);
+    storage::disable_contract_version(package_hash, previous_contract_hash).unwrap_or_revert();
+
+    // Chain name is used by the permit domain separator of contracts upgraded from a version
+    // without permits
+    let chain_name: String = runtime::get_named_arg("chain_name");
+    let _: () = runtime::call_contract(
+        contract_hash,
+        "migrate",
+        runtime_args! {
+            "chain_name" => chain_name,
+        },
+    );
+
+    contract_hash
+}
@@ -0,0 +1,173 @@
+//! ERC20Votes style vote delegation with checkpoints.
+//!
+//! Holders delegate voting power of their whole balance to a delegatee, possibly themselves,
+//! without moving tokens. Votes of every delegatee are kept as `(block_time, votes)` checkpoints,
+//! updated whenever balances of delegators change. Each checkpoint is a separate dictionary item,
+//! so updates and lookups do not read the whole history.
+use alloc::{format, string::String};
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::{Address, ERC20};
+use casper_types::U256;
+
+use crate::error::WcsprError;
+use crate::helpers::{address_to_key, get_uref};
+
+pub const DELEGATES_DICT_NAME: &str = "delegates";
+pub const VOTE_CHECKPOINTS_DICT_NAME: &str = "vote_checkpoints";
+pub const VOTE_CHECKPOINT_COUNTS_DICT_NAME: &str = "vote_checkpoint_counts";
+
+/// `(block_time, votes)` pair, checkpoints of an account are kept in increasing block time order.
+type Checkpoint = (u64, U256);
+
+pub fn init() {
+    storage::new_dictionary(DELEGATES_DICT_NAME).unwrap_or_revert();
+    storage::new_dictionary(VOTE_CHECKPOINTS_DICT_NAME).unwrap_or_revert();
+    storage::new_dictionary(VOTE_CHECKPOINT_COUNTS_DICT_NAME).unwrap_or_revert();
+}
+
+/// Returns delegatee of `account`, if any.
+pub fn delegates(account: Address) -> Option<Address> {
+    storage::dictionary_get(get_uref(DELEGATES_DICT_NAME), &address_to_key(&account))
+        .unwrap_or_revert()
+}
+
+/// Makes dictionary item key of `index` checkpoint of `account`.
+fn checkpoint_key(account: Address, index: u32) -> String {
+    format!("{}_{}", address_to_key(&account), index)
+}
+
+fn checkpoint_count(account: Address) -> u32 {
+    storage::dictionary_get(
+        get_uref(VOTE_CHECKPOINT_COUNTS_DICT_NAME),
+        &address_to_key(&account),
+    )
+    .unwrap_or_revert()
+    .unwrap_or_default()
+}
+
+fn checkpoint(account: Address, index: u32) -> Checkpoint {
+    storage::dictionary_get(
+        get_uref(VOTE_CHECKPOINTS_DICT_NAME),
+        &checkpoint_key(account, index),
+    )
+    .unwrap_or_revert()
+    .unwrap_or_revert_with(WcsprError::InvariantViolated)
+}
+
+fn set_checkpoint(account: Address, index: u32, checkpoint: Checkpoint) {
+    storage::dictionary_put(
+        get_uref(VOTE_CHECKPOINTS_DICT_NAME),
+        &checkpoint_key(account, index),
+        checkpoint,
+    );
+}
+
+/// Returns current votes of `account`.
+pub fn get_votes(account: Address) -> U256 {
+    match checkpoint_count(account) {
+        0 => U256::zero(),
+        count => checkpoint(account, count - 1).1,
+    }
+}
+
+/// Returns votes of `account` at the end of `timestamp` block time, that should be in the past.
+pub fn get_past_votes(account: Address, timestamp: u64) -> U256 {
+    if timestamp >= u64::from(runtime::get_blocktime()) {
+        runtime::revert(WcsprError::FutureLookup)
+    }
+
+    // Binary search of the number of checkpoints made at or before `timestamp`
+    let (mut low, mut high) = (0, checkpoint_count(account));
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if checkpoint(account, middle).0 <= timestamp {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    match low {
+        0 => U256::zero(),
+        count => checkpoint(account, count - 1).1,
+    }
+}
+
+fn write_checkpoint(account: Address, update: impl FnOnce(U256) -> U256) {
+    let count = checkpoint_count(account);
+    let block_time = u64::from(runtime::get_blocktime());
+    let last = if count > 0 {
+        Some(checkpoint(account, count - 1))
+    } else {
+        None
+    };
+    let votes = update(last.map(|(_, votes)| votes).unwrap_or_default());
+
+    // Several changes in the same block keep a single checkpoint
+    match last {
+        Some((last_block_time, _)) if last_block_time == block_time => {
+            set_checkpoint(account, count - 1, (block_time, votes));
+        }
+        _ => {
+            set_checkpoint(account, count, (block_time, votes));
+            storage::dictionary_put(
+                get_uref(VOTE_CHECKPOINT_COUNTS_DICT_NAME),
+                &address_to_key(&account),
+                count + 1,
+            );
+        }
+    }
+}
+
+fn move_voting_power(from: Option<Address>, to: Option<Address>, amount: U256) {
+    if from == to || amount.is_zero() {
+        return;
+    }
+    if let Some(from) = from {
+        write_checkpoint(from, |votes| {
+            votes
+                .checked_sub(amount)
+                .unwrap_or_revert_with(WcsprError::InvariantViolated)
+        });
+    }
+    if let Some(to) = to {
+        write_checkpoint(to, |votes| {
+            votes
+                .checked_add(amount)
+                .unwrap_or_revert_with(WcsprError::AmountOverflow)
+        });
+    }
+}
+
+/// Delegates votes of the whole `delegator` balance to `delegatee`.
+pub fn delegate(delegator: Address, delegatee: Address) {
+    let current_delegatee = delegates(delegator);
+    storage::dictionary_put(
+        get_uref(DELEGATES_DICT_NAME),
+        &address_to_key(&delegator),
+        delegatee,
+    );
+    move_voting_power(
+        current_delegatee,
+        Some(delegatee),
+        ERC20::default().balance_of(delegator),
+    );
+}
+
+/// Should be called when `amount` of WCSPR is moved from `sender` to `recipient`.
+pub fn on_transfer(sender: Address, recipient: Address, amount: U256) {
+    move_voting_power(delegates(sender), delegates(recipient), amount);
+}
+
+/// Should be called when `amount` of WCSPR is minted to `recipient`.
+pub fn on_mint(recipient: Address, amount: U256) {
+    move_voting_power(None, delegates(recipient), amount);
+}
+
+/// Should be called when `amount` of WCSPR is burned from `owner`.
+pub fn on_burn(owner: Address, amount: U256) {
+    move_voting_power(delegates(owner), None, amount);
+}
@@ -6,36 +6,72 @@ compile_error!("target arch should be wasm32: compile with '--target wasm32-unkn
 
 extern crate alloc;
 
+mod allowances;
+mod allowlist;
+mod circuit_breaker;
 mod entry_points;
+mod error;
+mod events;
+mod fees;
+mod flash_loan;
+mod freeze;
 mod helpers;
-
+mod limits;
+mod migrations;
+mod pause;
+mod permit;
+mod reserves;
+mod roles;
+mod self_transfer;
+mod snapshots;
+mod upgrade;
+mod votes;
+
+use crate::error::WcsprError;
+use crate::events::{emit, WcsprEvent};
+use crate::helpers::cspr_to_wcspr;
 use crate::helpers::get_immediate_caller_address;
 use crate::helpers::get_key;
 use crate::helpers::get_main_purse;
 use crate::helpers::set_key;
 use crate::helpers::set_main_purse;
-
-use alloc::string::String;
-use casper_types::ContractHash;
-use casper_types::HashAddr;
+use crate::helpers::wcspr_to_cspr;
+use crate::limits::check_deposit_limits;
+use crate::pause::{require_not_paused, Operation};
+use crate::roles::{require_role, Role};
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use casper_types::account::AccountHash;
+use casper_types::bytesrepr::Bytes;
+use casper_types::contracts::NamedKeys;
 use casper_types::Key;
-use casper_types::RuntimeArgs;
+use casper_types::{runtime_args, RuntimeArgs};
 
 use casper_contract::{
-    contract_api::{runtime, system},
+    contract_api::{runtime, storage, system},
     unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_erc20::{
     constants::{
-        ADDRESS_RUNTIME_ARG_NAME, AMOUNT_RUNTIME_ARG_NAME, DECIMALS_RUNTIME_ARG_NAME,
-        NAME_RUNTIME_ARG_NAME, OWNER_RUNTIME_ARG_NAME, RECIPIENT_RUNTIME_ARG_NAME,
-        SPENDER_RUNTIME_ARG_NAME, SYMBOL_RUNTIME_ARG_NAME, TOTAL_SUPPLY_RUNTIME_ARG_NAME,
+        ADDRESS_RUNTIME_ARG_NAME, ALLOWANCES_KEY_NAME, AMOUNT_RUNTIME_ARG_NAME, BALANCES_KEY_NAME,
+        DECIMALS_KEY_NAME, DECIMALS_RUNTIME_ARG_NAME, NAME_KEY_NAME, NAME_RUNTIME_ARG_NAME,
+        OWNER_RUNTIME_ARG_NAME, RECIPIENT_RUNTIME_ARG_NAME, SPENDER_RUNTIME_ARG_NAME,
+        SYMBOL_KEY_NAME, SYMBOL_RUNTIME_ARG_NAME, TOTAL_SUPPLY_KEY_NAME,
+        TOTAL_SUPPLY_RUNTIME_ARG_NAME,
     },
     Address, ERC20,
 };
-use casper_types::{CLValue, URef, U256, U512};
+use casper_types::{CLValue, ContractHash, ContractPackageHash, PublicKey, URef, U256, U512};
 
 const CONTRACT_KEY_NAME: &str = "wcspr_token";
+const CONTRACT_PACKAGE_KEY_NAME: &str = "wcspr_token_package_hash";
+const CONTRACT_ACCESS_KEY_NAME: &str = "wcspr_token_package_access";
+
+/// Max number of transfers in a single `batch_transfer` or `batch_transfer_from` call.
+const MAX_BATCH_SIZE: usize = 100;
 
 #[no_mangle]
 pub extern "C" fn name() {
@@ -70,20 +106,144 @@ pub extern "C" fn balance_of() {
 
 #[no_mangle]
 pub extern "C" fn transfer() {
+    require_not_paused(Operation::Transfer);
+
     let recipient: Address = runtime::get_named_arg(RECIPIENT_RUNTIME_ARG_NAME);
     let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
 
+    let sender = get_immediate_caller_address().unwrap_or_revert();
+    transfer_to(sender, recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn batch_transfer() {
+    require_not_paused(Operation::Transfer);
+
+    let recipients: Vec<(Address, U256)> = runtime::get_named_arg("recipients");
+    check_batch_size(&recipients);
+
+    let sender = get_immediate_caller_address().unwrap_or_revert();
+    for (recipient, amount) in recipients {
+        transfer_to(sender, recipient, amount);
+    }
+}
+
+/// Transfers `amount` of `sender` WCSPR to `recipient`, `sender` should be the caller.
+fn transfer_to(sender: Address, recipient: Address, amount: U256) {
+    freeze::require_not_frozen(sender);
+    freeze::require_not_frozen(recipient);
+
+    if self_transfer::is_self(recipient) {
+        unwrap_self_transfer(sender, amount);
+        return;
+    }
+
+    snapshots::before_transfer(sender, recipient);
     ERC20::default()
         .transfer(recipient, amount)
         .unwrap_or_revert();
+    votes::on_transfer(sender, recipient, amount);
+
+    emit(WcsprEvent::Transfer {
+        sender,
+        recipient,
+        amount,
+    });
+}
+
+/// Reverts if batch has more than [`MAX_BATCH_SIZE`] legs.
+fn check_batch_size(recipients: &[(Address, U256)]) {
+    if recipients.len() > MAX_BATCH_SIZE {
+        runtime::revert(WcsprError::BatchTooLarge)
+    }
 }
 
 #[no_mangle]
 pub extern "C" fn approve() {
+    require_not_paused(Operation::Approve);
+
     let spender: Address = runtime::get_named_arg(SPENDER_RUNTIME_ARG_NAME);
     let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
 
     ERC20::default().approve(spender, amount).unwrap_or_revert();
+
+    emit(WcsprEvent::Approval {
+        owner: get_immediate_caller_address().unwrap_or_revert(),
+        spender,
+        amount,
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn increase_allowance() {
+    require_not_paused(Operation::Approve);
+
+    let spender: Address = runtime::get_named_arg(SPENDER_RUNTIME_ARG_NAME);
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+
+    let owner = get_immediate_caller_address().unwrap_or_revert();
+    let allowance = allowances::increase_allowance(owner, spender, amount);
+
+    emit(WcsprEvent::Approval {
+        owner,
+        spender,
+        amount: allowance,
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn decrease_allowance() {
+    require_not_paused(Operation::Approve);
+
+    let spender: Address = runtime::get_named_arg(SPENDER_RUNTIME_ARG_NAME);
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+
+    let owner = get_immediate_caller_address().unwrap_or_revert();
+    let allowance = allowances::decrease_allowance(owner, spender, amount);
+
+    emit(WcsprEvent::Approval {
+        owner,
+        spender,
+        amount: allowance,
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn approve_if() {
+    require_not_paused(Operation::Approve);
+
+    let spender: Address = runtime::get_named_arg(SPENDER_RUNTIME_ARG_NAME);
+    let expected_current: U256 = runtime::get_named_arg("expected_current");
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+
+    let owner = get_immediate_caller_address().unwrap_or_revert();
+    allowances::approve_if(owner, spender, expected_current, amount);
+
+    emit(WcsprEvent::Approval {
+        owner,
+        spender,
+        amount,
+    });
+}
+
+#[no_mangle]
+pub extern "C" fn permit() {
+    require_not_paused(Operation::Approve);
+
+    let owner: PublicKey = runtime::get_named_arg(OWNER_RUNTIME_ARG_NAME);
+    let spender: Address = runtime::get_named_arg(SPENDER_RUNTIME_ARG_NAME);
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let signature: Bytes = runtime::get_named_arg("signature");
+
+    permit::permit(owner, spender, amount, deadline, signature);
+}
+
+#[no_mangle]
+pub extern "C" fn nonces() {
+    let owner: PublicKey = runtime::get_named_arg(OWNER_RUNTIME_ARG_NAME);
+    let nonce = permit::nonce(Address::from(owner.to_account_hash()));
+    runtime::ret(CLValue::from_t(nonce).unwrap_or_revert());
 }
 
 #[no_mangle]
@@ -96,12 +256,68 @@ pub extern "C" fn allowance() {
 
 #[no_mangle]
 pub extern "C" fn transfer_from() {
+    require_not_paused(Operation::Transfer);
+
     let owner: Address = runtime::get_named_arg(OWNER_RUNTIME_ARG_NAME);
     let recipient: Address = runtime::get_named_arg(RECIPIENT_RUNTIME_ARG_NAME);
     let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+
+    transfer_from_to(owner, recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn batch_transfer_from() {
+    require_not_paused(Operation::Transfer);
+
+    let owner: Address = runtime::get_named_arg(OWNER_RUNTIME_ARG_NAME);
+    let recipients: Vec<(Address, U256)> = runtime::get_named_arg("recipients");
+    check_batch_size(&recipients);
+
+    for (recipient, amount) in recipients {
+        transfer_from_to(owner, recipient, amount);
+    }
+}
+
+/// Transfers `amount` of `owner` WCSPR to `recipient`, spending allowance of the caller.
+fn transfer_from_to(owner: Address, recipient: Address, amount: U256) {
+    freeze::require_not_frozen(owner);
+    freeze::require_not_frozen(recipient);
+
+    if self_transfer::is_self(recipient) {
+        let spender = get_immediate_caller_address().unwrap_or_revert();
+        allowances::spend_allowance(owner, spender, amount);
+        unwrap_self_transfer(owner, amount);
+        return;
+    }
+
+    snapshots::before_transfer(owner, recipient);
     ERC20::default()
         .transfer_from(owner, recipient, amount)
         .unwrap_or_revert();
+    votes::on_transfer(owner, recipient, amount);
+
+    emit(WcsprEvent::Transfer {
+        sender: owner,
+        recipient,
+        amount,
+    });
+}
+
+/// Handles `amount` of `owner` WCSPR sent to the contract package itself.
+///
+/// Reverts, unless the contract was installed to unwrap such transfers. Then WCSPR are burned and
+/// CSPR is paid to the `owner` account, as if `owner` called `withdraw`.
+fn unwrap_self_transfer(owner: Address, amount: U256) {
+    if !self_transfer::unwraps_self_transfers() {
+        runtime::revert(WcsprError::SelfTransfer)
+    }
+
+    // Contracts should unwrap into their purse with `withdraw_to_purse`
+    let owner_account = *owner
+        .as_account_hash()
+        .unwrap_or_revert_with(WcsprError::SelfTransfer);
+
+    withdraw_to_account(owner, owner_account, wcspr_to_cspr(amount));
 }
 
 #[no_mangle]
@@ -109,8 +325,37 @@ pub extern "C" fn deposit() {
     // Get passed purse from pre_deposit
     let tmp_purse: URef = runtime::get_named_arg("tmp_purse");
 
+    // Get account of the user who called the contract
+    let sender = get_immediate_caller_address().unwrap_or_revert();
+
+    deposit_to(sender, tmp_purse);
+}
+
+#[no_mangle]
+pub extern "C" fn deposit_for() {
+    // Get passed purse from pre_deposit_for
+    let tmp_purse: URef = runtime::get_named_arg("tmp_purse");
+
+    // Account or contract package that receives WCSPR
+    let recipient: Address = runtime::get_named_arg(RECIPIENT_RUNTIME_ARG_NAME);
+
+    deposit_to(recipient, tmp_purse);
+}
+
+/// Moves all CSPR of `tmp_purse` into the contract, pays the deposit fee to the treasury and mints
+/// WCSPR for the rest of CSPR to `recipient`.
+fn deposit_to(recipient: Address, tmp_purse: URef) {
+    require_not_paused(Operation::Deposit);
+    freeze::require_not_frozen(recipient);
+
     let cspr_amount: U512 = system::get_purse_balance(tmp_purse).unwrap_or_revert();
-    let cspr_amount_u256: U256 = U256::from(cspr_amount.as_u128());
+    let fee: U512 = fees::deposit_fee(cspr_amount);
+    // Only CSPR left after the fee backs minted WCSPR
+    let cspr_amount_u256: U256 = cspr_to_wcspr(cspr_amount - fee);
+
+    // Stop deposit if it exceeds contract or user limits
+    check_deposit_limits(recipient, cspr_amount_u256);
+    allowlist::check_deposit(recipient, cspr_amount_u256);
 
     let contract_main_purse: URef = get_main_purse();
 
@@ -118,62 +363,451 @@ pub extern "C" fn deposit() {
         system::get_purse_balance(contract_main_purse).unwrap_or_revert();
 
     // Save CSPR provided by user into our contract
-    let _ = system::transfer_from_purse_to_purse(tmp_purse, contract_main_purse, cspr_amount, None);
+    system::transfer_from_purse_to_purse(tmp_purse, contract_main_purse, cspr_amount, None)
+        .unwrap_or_revert_with(WcsprError::PurseTransferFailed);
 
     let main_purse_balance_after: U512 =
         system::get_purse_balance(contract_main_purse).unwrap_or_revert();
-    assert_eq!(main_purse_balance + cspr_amount, main_purse_balance_after);
+    if main_purse_balance + cspr_amount != main_purse_balance_after {
+        runtime::revert(WcsprError::PurseTransferFailed)
+    }
 
-    // Get account of the user who called the contract
-    let sender = get_immediate_caller_address().unwrap_or_revert();
+    fees::pay_fee(contract_main_purse, fee);
 
-    // Issue WCSPR tokens to the sender
+    // Issue WCSPR tokens to the recipient
+    snapshots::before_mint_or_burn(recipient);
     ERC20::default()
-        .mint(sender, cspr_amount_u256)
-        .unwrap_or_revert();
+        .mint(recipient, cspr_amount_u256)
+        .unwrap_or_revert_with(WcsprError::AmountOverflow);
+    votes::on_mint(recipient, cspr_amount_u256);
+
+    emit(WcsprEvent::Deposit {
+        owner: recipient,
+        amount: cspr_amount_u256,
+        fee: U256::from(fee.as_u128()),
+    });
 
     // Save cspr balance
-    set_key(
-        "cspr_balance",
-        system::get_purse_balance(contract_main_purse).unwrap_or_revert(),
-    );
+    save_cspr_balance(contract_main_purse);
 }
 
 #[no_mangle]
 pub extern "C" fn withdraw() {
     // how many wcspr tokens to withdraw
     let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
-    let cspr_amount_u256: U256 = U256::from(cspr_amount.as_u128());
 
     // Get account of the user who called the contract
     let sender = get_immediate_caller_address().unwrap_or_revert();
 
-    let balance = ERC20::default().balance_of(sender);
+    withdraw_to_account(
+        sender,
+        *sender.as_account_hash().unwrap_or_revert(),
+        cspr_amount,
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn withdraw_to() {
+    // account that receives unwrapped CSPR
+    let recipient: AccountHash = runtime::get_named_arg("recipient");
+    // how many wcspr tokens to withdraw
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+
+    // WCSPR are burned from the caller
+    let sender = get_immediate_caller_address().unwrap_or_revert();
+
+    withdraw_to_account(sender, recipient, cspr_amount);
+}
+
+/// Burns WCSPR of the caller and sends CSPR to the passed `purse`, e.g. purse of the calling
+/// contract.
+///
+/// The contract only adds CSPR to `purse`, so callers should pass an add-only URef
+/// (`purse.into_add()`) instead of giving write access to their purse.
+#[no_mangle]
+pub extern "C" fn withdraw_to_purse() {
+    // purse that receives unwrapped CSPR
+    let purse: URef = runtime::get_named_arg("purse");
+    // how many wcspr tokens to withdraw
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+
+    // Account or contract package that called the contract
+    let sender = get_immediate_caller_address().unwrap_or_revert();
+
+    circuit_breaker::record_withdrawal(cspr_amount);
+
+    let (contract_main_purse, payout) = burn_for_withdrawal(sender, cspr_amount);
+
+    system::transfer_from_purse_to_purse(contract_main_purse, purse, payout, None)
+        .unwrap_or_revert_with(WcsprError::PurseTransferFailed);
+
+    save_cspr_balance(contract_main_purse);
+}
+
+/// Burns `cspr_amount` of `owner` WCSPR and sends the same amount of CSPR less the withdraw fee to
+/// `recipient` account.
+fn withdraw_to_account(owner: Address, recipient: AccountHash, cspr_amount: U512) {
+    circuit_breaker::record_withdrawal(cspr_amount);
+
+    let (contract_main_purse, payout) = burn_for_withdrawal(owner, cspr_amount);
+
+    system::transfer_from_purse_to_account(contract_main_purse, recipient, payout, None)
+        .unwrap_or_revert_with(WcsprError::PurseTransferFailed);
+
+    save_cspr_balance(contract_main_purse);
+}
+
+/// Checks `owner` balance and contract reserves, then burns `cspr_amount` of `owner` WCSPR and pays
+/// the withdraw fee to the treasury.
+///
+/// Returns contract main purse and amount of CSPR that should be paid out from it.
+fn burn_for_withdrawal(owner: Address, cspr_amount: U512) -> (URef, U512) {
+    require_not_paused(Operation::Withdraw);
+    freeze::require_not_frozen(owner);
+
+    let cspr_amount_u256: U256 = cspr_to_wcspr(cspr_amount);
+
+    let balance = ERC20::default().balance_of(owner);
+    if balance < cspr_amount_u256 {
+        runtime::revert(WcsprError::InsufficientBalance)
+    }
 
     let contract_main_purse = get_main_purse();
     let main_purse_balance: U512 =
         system::get_purse_balance(contract_main_purse).unwrap_or_revert();
-
-    if balance >= cspr_amount_u256 && cspr_amount <= main_purse_balance {
-        system::transfer_from_purse_to_account(
-            contract_main_purse,
-            *sender.as_account_hash().unwrap_or_revert(),
-            cspr_amount,
-            None,
-        )
-        .unwrap_or_revert();
-        ERC20::default()
-            .burn(sender, cspr_amount_u256)
-            .unwrap_or_revert();
+    if main_purse_balance < cspr_amount {
+        runtime::revert(WcsprError::InsufficientReserves)
     }
 
-    // Save cspr balance
+    snapshots::before_mint_or_burn(owner);
+    ERC20::default()
+        .burn(owner, cspr_amount_u256)
+        .unwrap_or_revert_with(WcsprError::InsufficientBalance);
+    votes::on_burn(owner, cspr_amount_u256);
+
+    let fee: U512 = fees::withdraw_fee(cspr_amount);
+    fees::pay_fee(contract_main_purse, fee);
+
+    emit(WcsprEvent::Withdrawal {
+        owner,
+        amount: cspr_amount_u256,
+        fee: U256::from(fee.as_u128()),
+    });
+
+    (contract_main_purse, cspr_amount - fee)
+}
+
+/// Saves CSPR balance of the contract main purse.
+fn save_cspr_balance(contract_main_purse: URef) {
     set_key(
         "cspr_balance",
         system::get_purse_balance(contract_main_purse).unwrap_or_revert(),
     );
 }
 
+#[no_mangle]
+pub extern "C" fn check_invariants() {
+    reserves::check_invariants();
+}
+
+#[no_mangle]
+pub extern "C" fn sync() {
+    // CSPR could land in the main purse without deposit, e.g. sent to the purse directly
+    save_cspr_balance(get_main_purse());
+}
+
+#[no_mangle]
+pub extern "C" fn skim() {
+    require_role(Role::Admin);
+
+    // treasury account that receives excess reserves
+    let recipient: AccountHash = runtime::get_named_arg("recipient");
+
+    let excess = reserves::excess_reserves();
+    if excess.is_zero() {
+        runtime::revert(WcsprError::ZeroAmount)
+    }
+
+    let contract_main_purse = get_main_purse();
+    system::transfer_from_purse_to_account(contract_main_purse, recipient, excess, None)
+        .unwrap_or_revert_with(WcsprError::PurseTransferFailed);
+
+    save_cspr_balance(contract_main_purse);
+}
+
+#[no_mangle]
+pub extern "C" fn flash_loan() {
+    require_not_paused(Operation::FlashLoan);
+
+    let receiver: ContractPackageHash = runtime::get_named_arg("receiver");
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+    let data: Bytes = runtime::get_named_arg("data");
+
+    flash_loan::flash_loan(receiver, amount, data);
+
+    save_cspr_balance(get_main_purse());
+}
+
+#[no_mangle]
+pub extern "C" fn flash_fee() {
+    let amount: U256 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG_NAME);
+    let fee = flash_loan::flash_fee(amount);
+    runtime::ret(CLValue::from_t(fee).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn max_flash_loan() {
+    let max_amount = flash_loan::max_flash_loan();
+    runtime::ret(CLValue::from_t(max_amount).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_flash_loan_config() {
+    require_role(Role::Admin);
+
+    let fee_bps: u32 = runtime::get_named_arg("fee_bps");
+    let max_amount: U256 = runtime::get_named_arg("max_amount");
+    flash_loan::set_config(fee_bps, max_amount);
+}
+
+#[no_mangle]
+pub extern "C" fn snapshot() {
+    require_role(Role::Admin);
+
+    let snapshot_id = snapshots::snapshot();
+    runtime::ret(CLValue::from_t(snapshot_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of_at() {
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let snapshot_id: u64 = runtime::get_named_arg("snapshot_id");
+    let balance = snapshots::balance_of_at(address, snapshot_id);
+    runtime::ret(CLValue::from_t(balance).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn total_supply_at() {
+    let snapshot_id: u64 = runtime::get_named_arg("snapshot_id");
+    let total_supply = snapshots::total_supply_at(snapshot_id);
+    runtime::ret(CLValue::from_t(total_supply).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn delegate() {
+    let delegatee: Address = runtime::get_named_arg("delegatee");
+
+    let delegator = get_immediate_caller_address().unwrap_or_revert();
+    votes::delegate(delegator, delegatee);
+}
+
+#[no_mangle]
+pub extern "C" fn delegates() {
+    let account: Address = runtime::get_named_arg("account");
+    let delegatee = votes::delegates(account);
+    runtime::ret(CLValue::from_t(delegatee).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_votes() {
+    let account: Address = runtime::get_named_arg("account");
+    let votes = votes::get_votes(account);
+    runtime::ret(CLValue::from_t(votes).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn get_past_votes() {
+    let account: Address = runtime::get_named_arg("account");
+    let timestamp: u64 = runtime::get_named_arg("timestamp");
+    let votes = votes::get_past_votes(account, timestamp);
+    runtime::ret(CLValue::from_t(votes).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn freeze() {
+    require_role(Role::Freezer);
+
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    freeze::freeze(address);
+}
+
+#[no_mangle]
+pub extern "C" fn unfreeze() {
+    require_role(Role::Freezer);
+
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    freeze::unfreeze(address);
+}
+
+#[no_mangle]
+pub extern "C" fn is_frozen() {
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let frozen = freeze::is_frozen(address);
+    runtime::ret(CLValue::from_t(frozen).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_withdrawal_limit() {
+    require_role(Role::Admin);
+
+    let window: u64 = runtime::get_named_arg("window");
+    let limit: U512 = runtime::get_named_arg("limit");
+    let pause_floor: U512 = runtime::get_named_arg("pause_floor");
+    circuit_breaker::set_config(window, limit, pause_floor);
+}
+
+#[no_mangle]
+pub extern "C" fn withdrawal_capacity() {
+    let capacity = circuit_breaker::capacity();
+    runtime::ret(CLValue::from_t(capacity).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn fee_config() {
+    let config = fees::get_config();
+    runtime::ret(CLValue::from_t(config).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_fee_config() {
+    require_role(Role::Admin);
+
+    let deposit_fee_bps: u32 = runtime::get_named_arg("deposit_fee_bps");
+    let withdraw_fee_bps: u32 = runtime::get_named_arg("withdraw_fee_bps");
+    let treasury: AccountHash = runtime::get_named_arg("treasury");
+    fees::set_config(deposit_fee_bps, withdraw_fee_bps, treasury);
+}
+
+#[no_mangle]
+pub extern "C" fn set_limits() {
+    require_role(Role::LimitsManager);
+
+    let user_limit: U256 = runtime::get_named_arg("user_limit");
+    let contract_limit: U256 = runtime::get_named_arg("contract_limit");
+    limits::set_limits(user_limit, contract_limit);
+}
+
+#[no_mangle]
+pub extern "C" fn get_limits() {
+    let limits = limits::get_limits();
+    runtime::ret(CLValue::from_t(limits).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn remaining_quota() {
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let quota = limits::remaining_quota(address);
+    runtime::ret(CLValue::from_t(quota).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_allowlist_enabled() {
+    require_role(Role::LimitsManager);
+
+    let enabled: bool = runtime::get_named_arg("enabled");
+    allowlist::set_enabled(enabled);
+}
+
+#[no_mangle]
+pub extern "C" fn set_tier_caps() {
+    require_role(Role::LimitsManager);
+
+    let tier: u8 = runtime::get_named_arg("tier");
+    let max_holding: U256 = runtime::get_named_arg("max_holding");
+    let max_deposit: U256 = runtime::get_named_arg("max_deposit");
+    allowlist::set_tier_caps(tier, max_holding, max_deposit);
+}
+
+#[no_mangle]
+pub extern "C" fn set_address_tier() {
+    require_role(Role::LimitsManager);
+
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let tier: u8 = runtime::get_named_arg("tier");
+    allowlist::set_address_tier(address, tier);
+}
+
+#[no_mangle]
+pub extern "C" fn tier_of() {
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let tier = allowlist::tier_of(address);
+    runtime::ret(CLValue::from_t(tier).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn tier_caps() {
+    let tier: u8 = runtime::get_named_arg("tier");
+    let caps = allowlist::tier_caps(tier);
+    runtime::ret(CLValue::from_t(caps).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grant_role() {
+    require_role(Role::Admin);
+
+    let role: String = runtime::get_named_arg("role");
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    roles::grant_role(Role::from_name(&role), address);
+}
+
+#[no_mangle]
+pub extern "C" fn revoke_role() {
+    require_role(Role::Admin);
+
+    let role: String = runtime::get_named_arg("role");
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    roles::revoke_role(Role::from_name(&role), address);
+}
+
+#[no_mangle]
+pub extern "C" fn has_role() {
+    let role: String = runtime::get_named_arg("role");
+    let address: Address = runtime::get_named_arg(ADDRESS_RUNTIME_ARG_NAME);
+    let value = roles::has_role(Role::from_name(&role), address);
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn pause() {
+    require_role(Role::Pauser);
+
+    let operations: Vec<String> = runtime::get_named_arg("operations");
+    for operation in operations {
+        pause::set_paused(Operation::from_name(&operation), true);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn unpause() {
+    require_role(Role::Pauser);
+
+    let operations: Vec<String> = runtime::get_named_arg("operations");
+    for operation in operations {
+        pause::set_paused(Operation::from_name(&operation), false);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn is_paused() {
+    let operation: String = runtime::get_named_arg("operation");
+    let paused = pause::is_paused(Operation::from_name(&operation));
+    runtime::ret(CLValue::from_t(paused).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn named_keys_for_upgrade() {
+    let package_access: URef = runtime::get_named_arg("package_access");
+    let named_keys = upgrade::named_keys(package_access);
+    runtime::ret(CLValue::from_t(named_keys).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn migrate() {
+    require_role(Role::Admin);
+
+    migrations::migrate();
+}
+
 #[no_mangle]
 pub extern "C" fn init() {
     let value: Option<bool> = get_key("initialized");
@@ -181,6 +815,39 @@ pub extern "C" fn init() {
         Some(_) => {}
         None => {
             set_main_purse(system::create_purse());
+
+            // ERC20 dictionaries
+            storage::new_dictionary(BALANCES_KEY_NAME).unwrap_or_revert();
+            storage::new_dictionary(ALLOWANCES_KEY_NAME).unwrap_or_revert();
+
+            // Installer is the initial admin holding every role
+            let admin = get_immediate_caller_address().unwrap_or_revert();
+            roles::init(admin);
+
+            // Fees are disabled until admin sets them, installer is the initial treasury
+            fees::init(*admin.as_account_hash().unwrap_or_revert());
+
+            // Guarded launch limits
+            let user_limit: U256 = runtime::get_named_arg("user_limit");
+            let contract_limit: U256 = runtime::get_named_arg("contract_limit");
+            limits::set_limits(user_limit, contract_limit);
+
+            events::init();
+            pause::init();
+            flash_loan::init();
+            snapshots::init();
+            votes::init();
+            freeze::init();
+            circuit_breaker::init();
+            allowlist::init();
+
+            let chain_name: String = runtime::get_named_arg("chain_name");
+            permit::init(chain_name);
+
+            let unwrap_self_transfers: bool = runtime::get_named_arg("unwrap_self_transfers");
+            self_transfer::init(unwrap_self_transfers);
+
+            migrations::set_storage_version(migrations::STORAGE_VERSION);
             set_key("initialized", true);
         }
     }
@@ -188,23 +855,90 @@ pub extern "C" fn init() {
 
 #[no_mangle]
 fn call() {
+    // Installer keeps the package hash, running this session code again upgrades the contract
+    match runtime::get_key(CONTRACT_PACKAGE_KEY_NAME) {
+        Some(package_key) => upgrade_contract(package_key),
+        None => install_contract(),
+    }
+}
+
+/// Adds a new contract version to the package installed by the caller.
+fn upgrade_contract(package_key: Key) {
+    let package_hash = ContractPackageHash::new(package_key.into_hash().unwrap_or_revert());
+    let package_access: URef = runtime::get_key(CONTRACT_ACCESS_KEY_NAME)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert();
+    let previous_contract_hash = ContractHash::new(
+        runtime::get_key(CONTRACT_KEY_NAME)
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+
+    let contract_hash = upgrade::upgrade(package_hash, package_access, previous_contract_hash);
+    runtime::put_key(CONTRACT_KEY_NAME, Key::from(contract_hash));
+}
+
+fn install_contract() {
     let name: String = runtime::get_named_arg(NAME_RUNTIME_ARG_NAME);
     let symbol: String = runtime::get_named_arg(SYMBOL_RUNTIME_ARG_NAME);
-    let decimals = runtime::get_named_arg(DECIMALS_RUNTIME_ARG_NAME);
-    let initial_supply = runtime::get_named_arg(TOTAL_SUPPLY_RUNTIME_ARG_NAME);
-
-    let _ = ERC20::install_custom(
-        name,
-        symbol,
-        decimals,
-        initial_supply,
-        CONTRACT_KEY_NAME,
-        entry_points::default(),
+    let decimals: u8 = runtime::get_named_arg(DECIMALS_RUNTIME_ARG_NAME);
+    let initial_supply: U256 = runtime::get_named_arg(TOTAL_SUPPLY_RUNTIME_ARG_NAME);
+    // Every WCSPR is minted by a deposit, initial supply would not be backed by any CSPR
+    if !initial_supply.is_zero() {
+        runtime::revert(WcsprError::InvariantViolated)
+    }
+    let user_limit: U256 = runtime::get_named_arg("user_limit");
+    let contract_limit: U256 = runtime::get_named_arg("contract_limit");
+    let chain_name: String = runtime::get_named_arg("chain_name");
+    let unwrap_self_transfers: bool = runtime::get_named_arg("unwrap_self_transfers");
+
+    // Same ERC20 named keys as `ERC20::install_custom` creates, balances and allowances
+    // dictionaries are created by `init` in the contract context
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        NAME_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(name).into_read()),
+    );
+    named_keys.insert(
+        SYMBOL_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(symbol).into_read()),
+    );
+    named_keys.insert(
+        DECIMALS_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(decimals).into_read()),
+    );
+    // Total supply is changed on every deposit and withdraw
+    named_keys.insert(
+        TOTAL_SUPPLY_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(U256::zero()).into_read_write()),
     );
 
-    let key: Key = runtime::get_key(CONTRACT_KEY_NAME).unwrap_or_revert();
-    let hash: HashAddr = key.into_hash().unwrap_or_revert();
-    let contract_hash = ContractHash::new(hash);
+    // Package is created first, so the contract could check the access URef on upgrades
+    let (package_hash, package_access) = storage::create_contract_package_at_hash();
+    named_keys.insert(
+        upgrade::PACKAGE_ACCESS_KEY_NAME.to_string(),
+        Key::from(storage::new_uref(package_access.addr()).into_read()),
+    );
 
-    let _: () = runtime::call_contract(contract_hash, "init", RuntimeArgs::new());
+    let (contract_hash, _) =
+        storage::add_contract_version(package_hash, entry_points::default(), named_keys);
+
+    // Package hash and access URef are kept by the installer, so new contract versions could
+    // be added to the package later by running this session code again
+    runtime::put_key(CONTRACT_PACKAGE_KEY_NAME, Key::from(package_hash));
+    runtime::put_key(CONTRACT_ACCESS_KEY_NAME, Key::from(package_access));
+    runtime::put_key(CONTRACT_KEY_NAME, Key::from(contract_hash));
+
+    let _: () = runtime::call_contract(
+        contract_hash,
+        "init",
+        runtime_args! {
+            "user_limit" => user_limit,
+            "contract_limit" => contract_limit,
+            "chain_name" => chain_name,
+            "unwrap_self_transfers" => unwrap_self_transfers,
+        },
+    );
 }
@@ -0,0 +1,78 @@
+#![no_main]
+
+use casper_contract::{
+    contract_api::{account, runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::Address;
+use casper_types::RuntimeArgs;
+use casper_types::{runtime_args, ContractHash, HashAddr, Key, URef, U256, U512};
+
+#[no_mangle]
+fn call() {
+    // Same as `pre_deposit`, then the `spender` (e.g. a DEX router) is approved to spend WCSPR
+    // of the user, so a swap needs only one preparation deploy
+
+    // how many cspr tokens to transfer
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+
+    // who is approved to spend WCSPR tokens
+    let spender: Address = runtime::get_named_arg("spender");
+
+    // allowance to set, `None` approves exactly the minted amount
+    let amount: Option<U256> = runtime::get_named_arg("amount");
+
+    // WCSPR contract hash address passed as an argument to this contract
+    let wcspr_contract_key: Key = runtime::get_named_arg("wcspr_contract_hash_key");
+    let _wcspr_contract_hash: HashAddr = wcspr_contract_key.into_hash().unwrap_or_revert();
+    let wcspr_contract_hash: ContractHash = ContractHash::new(_wcspr_contract_hash);
+
+    // User who call the contract, WCSPR are minted to him
+    let sender = Address::from(runtime::get_caller());
+    let balance_before: U256 = runtime::call_contract(
+        wcspr_contract_hash,
+        "balance_of",
+        runtime_args! {
+            "address" => sender
+        },
+    );
+
+    // Purse with CSPR tokens of the user who call the contract
+    let sender_purse: URef = account::get_main_purse();
+
+    // Here we put tokens we want to transfer to the contract
+    let tmp_purse: URef = system::create_purse();
+
+    // move from sender to tmp purse, so we can use tmp purse in the next contract
+    system::transfer_from_purse_to_purse(sender_purse, tmp_purse, cspr_amount, None)
+        .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(
+        wcspr_contract_hash,
+        "deposit",
+        runtime_args! {
+            "tmp_purse" => tmp_purse
+        },
+    );
+
+    let amount = amount.unwrap_or_else(|| {
+        // Deposit fee could make minted amount lower than `cspr_amount`
+        let balance_after: U256 = runtime::call_contract(
+            wcspr_contract_hash,
+            "balance_of",
+            runtime_args! {
+                "address" => sender
+            },
+        );
+        balance_after - balance_before
+    });
+
+    runtime::call_contract(
+        wcspr_contract_hash,
+        "approve",
+        runtime_args! {
+            "spender" => spender,
+            "amount" => amount
+        },
+    )
+}
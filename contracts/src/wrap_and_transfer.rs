@@ -0,0 +1,46 @@
+#![no_main]
+
+use casper_contract::{
+    contract_api::{account, runtime, system},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_erc20::Address;
+use casper_types::RuntimeArgs;
+use casper_types::{runtime_args, ContractHash, HashAddr, Key, URef, U512};
+
+#[no_mangle]
+fn call() {
+    // Pays the `recipient` (e.g. a merchant) in WCSPR from CSPR within one deploy. WCSPR are
+    // minted to the `recipient` directly, so deposit limits, allowlist tier and freeze status of
+    // the `recipient` are checked, not the ones of the user who call the contract
+
+    // how many cspr tokens to transfer
+    let cspr_amount: U512 = runtime::get_named_arg("cspr_amount");
+
+    // who receives WCSPR tokens
+    let recipient: Address = runtime::get_named_arg("recipient");
+
+    // WCSPR contract hash address passed as an argument to this contract
+    let wcspr_contract_key: Key = runtime::get_named_arg("wcspr_contract_hash_key");
+    let _wcspr_contract_hash: HashAddr = wcspr_contract_key.into_hash().unwrap_or_revert();
+    let wcspr_contract_hash: ContractHash = ContractHash::new(_wcspr_contract_hash);
+
+    // Purse with CSPR tokens of the user who call the contract
+    let sender_purse: URef = account::get_main_purse();
+
+    // Here we put tokens we want to transfer to the contract
+    let tmp_purse: URef = system::create_purse();
+
+    // move from sender to tmp purse, so we can use tmp purse in the next contract
+    system::transfer_from_purse_to_purse(sender_purse, tmp_purse, cspr_amount, None)
+        .unwrap_or_revert();
+
+    runtime::call_contract(
+        wcspr_contract_hash,
+        "deposit_for",
+        runtime_args! {
+            "tmp_purse" => tmp_purse,
+            "recipient" => recipient
+        },
+    )
+}
@@ -4,9 +4,9 @@ mod test_fixture;
 #[cfg(test)]
 mod tests {
     // use casper_engine_test_support::TestContext;
-    use casper_types::{Key, U256, U512};
+    use casper_types::{account::AccountHash, bytesrepr::Bytes, Key, U256, U512};
 
-    use crate::test_fixture::{Sender, TestFixture};
+    use crate::test_fixture::{Event, GasFixture, Sender, TestFixture};
 
     #[test]
     fn should_install() {
@@ -20,6 +20,166 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_store_deposit_limits() {
+        let fixture = TestFixture::install_contract();
+        assert_eq!(fixture.user_limit(), U256::from(TestFixture::USER_LIMIT));
+        assert_eq!(
+            fixture.contract_limit(),
+            U256::from(TestFixture::CONTRACT_LIMIT)
+        );
+    }
+
+    #[test]
+    fn should_set_limits() {
+        let mut fixture = TestFixture::install_contract();
+
+        let user_limit = U256::from(150) * (U256::from(10)).pow(U256::from(9));
+        let contract_limit = U256::from(300) * (U256::from(10)).pow(U256::from(9));
+        fixture.set_limits(user_limit, contract_limit, Sender(fixture.ali));
+
+        assert_eq!(fixture.user_limit(), user_limit);
+        assert_eq!(fixture.contract_limit(), contract_limit);
+
+        // deposit above the initial user limit is accepted now
+        let cspr_deposit_amount = U512::from(150) * (U512::from(10)).pow(U512::from(9));
+        fixture.deposit(Sender(fixture.ali), cspr_deposit_amount);
+
+        assert_eq!(fixture.balance_of(Key::from(fixture.ali)), Some(user_limit));
+        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_set_limits_without_role() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.set_limits(U256::max_value(), U256::max_value(), Sender(fixture.bob));
+    }
+
+    const RETAIL_TIER: u8 = 1;
+    const MARKET_MAKER_TIER: u8 = 3;
+
+    /// Installs contract with enabled allowlist: retail tier holds up to 50 CSPR with deposits of
+    /// 20 CSPR at most, market maker tier has no caps below the global limits.
+    fn install_with_allowlist() -> TestFixture {
+        let mut fixture = TestFixture::install_contract();
+        let manager = Sender(fixture.ali);
+
+        fixture.set_tier_caps(RETAIL_TIER, U256::from(50), U256::from(20), manager);
+        fixture.set_tier_caps(
+            MARKET_MAKER_TIER,
+            U256::max_value(),
+            U256::max_value(),
+            manager,
+        );
+        fixture.set_address_tier(Key::from(fixture.bob), RETAIL_TIER, manager);
+        fixture.set_address_tier(Key::from(fixture.ali), MARKET_MAKER_TIER, manager);
+        fixture.set_allowlist_enabled(true, manager);
+        fixture
+    }
+
+    #[test]
+    fn should_deposit_within_tier_caps() {
+        let mut fixture = install_with_allowlist();
+        assert_eq!(fixture.tier_of(Key::from(fixture.bob)), RETAIL_TIER);
+
+        fixture.deposit(Sender(fixture.bob), U512::from(20));
+        fixture.deposit(Sender(fixture.bob), U512::from(20));
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.bob)),
+            Some(U256::from(40))
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(1000))
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(27) [65563]")]
+    #[test]
+    fn should_not_deposit_to_not_allowlisted_address() {
+        let mut fixture = install_with_allowlist();
+
+        fixture.set_address_tier(Key::from(fixture.bob), 0, Sender(fixture.ali));
+        fixture.deposit(Sender(fixture.bob), U512::from(10));
+    }
+
+    #[should_panic(expected = "ApiError::User(28) [65564]")]
+    #[test]
+    fn should_not_deposit_over_tier_deposit_cap() {
+        let mut fixture = install_with_allowlist();
+
+        fixture.deposit(Sender(fixture.bob), U512::from(21));
+    }
+
+    #[should_panic(expected = "ApiError::User(29) [65565]")]
+    #[test]
+    fn should_not_deposit_over_tier_max_holding() {
+        let mut fixture = install_with_allowlist();
+
+        fixture.deposit(Sender(fixture.bob), U512::from(20));
+        fixture.deposit(Sender(fixture.bob), U512::from(20));
+        fixture.deposit(Sender(fixture.bob), U512::from(20));
+    }
+
+    #[test]
+    fn should_deposit_after_allowlist_is_disabled() {
+        let mut fixture = install_with_allowlist();
+
+        fixture.set_allowlist_enabled(false, Sender(fixture.ali));
+        fixture.deposit(Sender(fixture.bob), U512::from(100));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.bob)),
+            Some(U256::from(100))
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(30) [65566]")]
+    #[test]
+    fn should_not_set_address_tier_without_caps() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.set_address_tier(Key::from(fixture.bob), 2, Sender(fixture.ali));
+    }
+
+    #[test]
+    fn should_grant_installer_every_role() {
+        let fixture = TestFixture::install_contract();
+
+        for role in &["admin", "pauser", "limits_manager", "freezer"] {
+            assert!(fixture.has_role(role, Key::from(fixture.ali)));
+            assert!(!fixture.has_role(role, Key::from(fixture.bob)));
+        }
+    }
+
+    #[test]
+    fn should_grant_and_revoke_role() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+        let bob = Key::from(fixture.bob);
+
+        fixture.grant_role("limits_manager", bob, admin);
+        assert!(fixture.has_role("limits_manager", bob));
+
+        let user_limit = U256::from(150) * (U256::from(10)).pow(U256::from(9));
+        fixture.set_limits(user_limit, user_limit, Sender(fixture.bob));
+        assert_eq!(fixture.user_limit(), user_limit);
+
+        fixture.revoke_role("limits_manager", bob, admin);
+        assert!(!fixture.has_role("limits_manager", bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_grant_role_by_non_admin() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.grant_role("admin", Key::from(fixture.bob), Sender(fixture.bob));
+    }
+
     #[test]
     fn should_deposit() {
         let mut fixture = TestFixture::install_contract();
@@ -40,6 +200,142 @@ mod tests {
         assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
     }
 
+    #[test]
+    fn should_deposit_for() {
+        let mut fixture = TestFixture::install_contract();
+
+        let cspr_deposit_amount = U512::from(100);
+        let deposited_wcspr = U256::from(100);
+
+        fixture.deposit_for(
+            Key::from(fixture.joe),
+            cspr_deposit_amount,
+            Sender(fixture.ali),
+        );
+
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.joe)),
+            Some(deposited_wcspr)
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::zero())
+        );
+        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+    }
+
+    #[test]
+    fn should_deposit_for_contract_package() {
+        let mut fixture = TestFixture::install_contract();
+
+        let package = Key::Hash([42u8; 32]);
+        fixture.deposit_for(package, U512::from(100), Sender(fixture.ali));
+
+        assert_eq!(fixture.balance_of(package), Some(U256::from(100)));
+    }
+
+    #[should_panic(expected = "ApiError::User(0) [65536]")]
+    #[test]
+    fn should_not_deposit_for_more_then_recipient_limit() {
+        let mut fixture = TestFixture::install_contract();
+
+        let cspr_deposit_amount = U512::from(60) * (U512::from(10)).pow(U512::from(9));
+        let joe = Key::from(fixture.joe);
+
+        fixture.deposit_for(joe, cspr_deposit_amount, Sender(fixture.ali));
+        fixture.deposit_for(joe, cspr_deposit_amount, Sender(fixture.bob));
+    }
+
+    #[test]
+    fn should_wrap_and_transfer() {
+        let mut fixture = TestFixture::install_contract();
+        let merchant = Key::from(fixture.joe);
+
+        fixture.wrap_and_transfer(merchant, U512::from(100), Sender(fixture.ali));
+
+        assert_eq!(fixture.balance_of(merchant), Some(U256::from(100)));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::zero())
+        );
+        assert_eq!(fixture.cspr_balance(), U512::from(100));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Deposit {
+                owner: merchant,
+                amount: U256::from(100),
+                fee: U256::zero()
+            }
+        );
+    }
+
+    #[test]
+    fn should_wrap_and_transfer_with_sender_at_user_limit() {
+        let mut fixture = TestFixture::install_contract();
+        let merchant = Key::from(fixture.joe);
+
+        // only the recipient deposit limit is checked
+        fixture.deposit(Sender(fixture.ali), U512::from(TestFixture::USER_LIMIT));
+        fixture.wrap_and_transfer(merchant, U512::from(100), Sender(fixture.ali));
+
+        assert_eq!(fixture.balance_of(merchant), Some(U256::from(100)));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(TestFixture::USER_LIMIT))
+        );
+    }
+
+    #[test]
+    fn should_wrap_and_transfer_amount_left_after_deposit_fee() {
+        let mut fixture = TestFixture::install_contract();
+        let merchant = Key::from(fixture.joe);
+        let treasury = fixture.dan;
+
+        // 1% deposit fee
+        fixture.set_fee_config(100, 0, treasury, Sender(fixture.ali));
+        fixture.wrap_and_transfer(merchant, U512::from(1000), Sender(fixture.ali));
+
+        assert_eq!(fixture.balance_of(merchant), Some(U256::from(990)));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::zero())
+        );
+    }
+
+    #[test]
+    fn should_wrap_and_approve() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = Key::from(fixture.ali);
+        let router = Key::from(fixture.joe);
+
+        fixture.wrap_and_approve(
+            router,
+            Some(U256::from(50)),
+            U512::from(100),
+            Sender(fixture.ali),
+        );
+
+        assert_eq!(fixture.balance_of(owner), Some(U256::from(100)));
+        assert_eq!(fixture.allowance(owner, router), Some(U256::from(50)));
+        assert_eq!(fixture.cspr_balance(), U512::from(100));
+    }
+
+    #[test]
+    fn should_wrap_and_approve_minted_amount() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = Key::from(fixture.ali);
+        let router = Key::from(fixture.joe);
+        let treasury = fixture.dan;
+
+        fixture.deposit(Sender(fixture.ali), U512::from(300));
+        // 1% deposit fee
+        fixture.set_fee_config(100, 0, treasury, Sender(fixture.ali));
+        fixture.wrap_and_approve(router, None, U512::from(1000), Sender(fixture.ali));
+
+        assert_eq!(fixture.balance_of(owner), Some(U256::from(1290)));
+        assert_eq!(fixture.allowance(owner, router), Some(U256::from(990)));
+    }
+
     #[test]
     fn should_deposit_max_user_limit() {
         let mut fixture = TestFixture::install_contract();
@@ -182,124 +478,374 @@ mod tests {
     }
 
     #[test]
-    fn should_transfer() {
+    fn should_withdraw_to() {
         let mut fixture = TestFixture::install_contract();
-
-        // Deposit WCPSR to ali balance
-        let cspr_deposit_amount = U512::from(1000);
-        let deposited_wcspr = U256::from(1000);
         let sender = Sender(fixture.ali);
 
-        let initial_balance = fixture.balance_of(Key::from(fixture.ali)).unwrap();
-        let expected_balance = initial_balance + deposited_wcspr;
-
-        fixture.deposit(sender, cspr_deposit_amount);
+        fixture.deposit(sender, U512::from(42));
 
-        assert_eq!(
-            fixture.balance_of(Key::from(fixture.ali)),
-            Some(expected_balance)
-        );
-        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+        let bob_cspr_before = fixture.account_cspr_balance(fixture.bob);
+        fixture.withdraw_to(fixture.bob, U512::from(12), sender);
 
-        // Make transfer from ali to bob
-        assert_eq!(fixture.balance_of(Key::from(fixture.bob)), None);
-        assert_eq!(
-            fixture.balance_of(Key::from(fixture.ali)),
-            Some(fixture.token_total_supply())
-        );
-        let transfer_amount_1 = U256::from(42);
-        fixture.transfer(
-            Key::from(fixture.bob),
-            transfer_amount_1,
-            Sender(fixture.ali),
-        );
-        assert_eq!(
-            fixture.balance_of(Key::from(fixture.bob)),
-            Some(transfer_amount_1)
-        );
         assert_eq!(
-            fixture.balance_of(Key::from(fixture.ali)),
-            Some(fixture.token_total_supply() - transfer_amount_1)
-        );
-
-        // Make transfer from bob to ali
-        let transfer_amount_2 = U256::from(20);
-        fixture.transfer(
-            Key::from(fixture.ali),
-            transfer_amount_2,
-            Sender(fixture.bob),
+            fixture.account_cspr_balance(fixture.bob),
+            bob_cspr_before + U512::from(12)
         );
+        assert_eq!(fixture.cspr_balance(), U512::from(30));
         assert_eq!(
             fixture.balance_of(Key::from(fixture.ali)),
-            Some(fixture.token_total_supply() - transfer_amount_1 + transfer_amount_2),
-        );
-        assert_eq!(
-            fixture.balance_of(Key::from(fixture.bob)),
-            Some(transfer_amount_1 - transfer_amount_2)
+            Some(U256::from(30))
         );
+        assert_eq!(fixture.balance_of(Key::from(fixture.bob)), None);
     }
 
+    #[should_panic(expected = "ApiError::User(2) [65538]")]
     #[test]
-    fn should_transfer_from() {
-        let approve_amount = U256::from(100);
-        let transfer_amount = U256::from(42);
-        assert!(approve_amount > transfer_amount);
-
+    fn should_not_withdraw_to_more_than_balance() {
         let mut fixture = TestFixture::install_contract();
-
-        let owner = fixture.ali;
-        let spender = fixture.bob;
-        let recipient = fixture.joe;
-
-        // Deposit WCPSR to ali balance
-        let cspr_deposit_amount = U512::from(1000);
-        let deposited_wcspr = U256::from(1000);
         let sender = Sender(fixture.ali);
 
-        let initial_balance = fixture.balance_of(Key::from(fixture.ali)).unwrap();
-        let expected_balance = initial_balance + deposited_wcspr;
+        fixture.deposit(sender, U512::from(42));
 
-        fixture.deposit(sender, cspr_deposit_amount);
+        fixture.withdraw_to(fixture.bob, U512::from(43), sender);
+    }
 
-        assert_eq!(
-            fixture.balance_of(Key::from(fixture.ali)),
-            Some(expected_balance)
-        );
-        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+    #[test]
+    fn should_withdraw_to_purse_from_contract() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_vault();
+        let vault = fixture.test_vault();
 
-        // Approve ali WCSPR to be spent by bob
-        let owner_balance_before = fixture
-            .balance_of(Key::from(owner))
-            .expect("owner should have balance");
-        fixture.approve(Key::from(spender), approve_amount, Sender(owner));
-        assert_eq!(
-            fixture.allowance(Key::from(owner), Key::from(spender)),
-            Some(approve_amount)
-        );
+        // vault contract holds WCSPR
+        fixture.deposit_for(vault, U512::from(42), Sender(fixture.ali));
+        assert_eq!(fixture.balance_of(vault), Some(U256::from(42)));
 
-        // Bob transfer WCSPR of ali to joe
-        fixture.transfer_from(
-            Key::from(owner),
-            Key::from(recipient),
-            transfer_amount,
-            Sender(spender),
-        );
+        // and unwraps them into its own purse
+        fixture.test_vault_unwrap(U512::from(12), Sender(fixture.bob));
 
-        assert_eq!(
-            fixture.balance_of(Key::from(owner)),
-            Some(owner_balance_before - transfer_amount),
-            "should decrease balance of the owner"
-        );
-        assert_eq!(
-            fixture.allowance(Key::from(owner), Key::from(spender)),
-            Some(approve_amount - transfer_amount),
-            "should decrease allowance of the spender"
-        );
-        assert_eq!(
-            fixture.balance_of(Key::from(recipient)),
-            Some(transfer_amount),
-            "recipient should receive tokens"
-        );
+        assert_eq!(fixture.test_vault_cspr_balance(), U512::from(12));
+        assert_eq!(fixture.balance_of(vault), Some(U256::from(30)));
+        assert_eq!(fixture.cspr_balance(), U512::from(30));
+    }
+
+    #[should_panic(expected = "ApiError::User(2) [65538]")]
+    #[test]
+    fn should_not_withdraw_to_purse_more_than_contract_balance() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_vault();
+        let vault = fixture.test_vault();
+
+        fixture.deposit_for(vault, U512::from(42), Sender(fixture.ali));
+        fixture.deposit(Sender(fixture.ali), U512::from(100));
+
+        fixture.test_vault_unwrap(U512::from(43), Sender(fixture.ali));
+    }
+
+    #[should_panic(expected = "ApiError::User(2) [65538]")]
+    #[test]
+    fn should_not_withdraw_more_than_balance() {
+        let mut fixture = TestFixture::install_contract();
+
+        let sender = Sender(fixture.ali);
+        fixture.deposit(sender, U512::from(42));
+
+        fixture.withdraw(sender, U512::from(43));
+    }
+
+    #[should_panic(expected = "ApiError::User(5) [65541]")]
+    #[test]
+    fn should_not_withdraw_zero_amount() {
+        let mut fixture = TestFixture::install_contract();
+
+        let sender = Sender(fixture.ali);
+        fixture.deposit(sender, U512::from(42));
+
+        fixture.withdraw(sender, U512::zero());
+    }
+
+    #[should_panic(expected = "ApiError::User(5) [65541]")]
+    #[test]
+    fn should_not_deposit_zero_amount() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.deposit(Sender(fixture.ali), U512::zero());
+    }
+
+    #[test]
+    fn should_withdraw_while_deposits_paused() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(42));
+
+        fixture.pause(&["deposit"], sender);
+        assert!(fixture.is_paused("deposit"));
+        assert!(!fixture.is_paused("withdraw"));
+
+        fixture.withdraw(sender, U512::from(42));
+        assert_eq!(fixture.cspr_balance(), U512::zero());
+
+        fixture.unpause(&["deposit"], sender);
+        assert!(!fixture.is_paused("deposit"));
+
+        fixture.deposit(sender, U512::from(42));
+        assert_eq!(fixture.cspr_balance(), U512::from(42));
+    }
+
+    #[should_panic(expected = "ApiError::User(10) [65546]")]
+    #[test]
+    fn should_not_deposit_when_paused() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.pause(&["deposit"], sender);
+        fixture.deposit(sender, U512::from(42));
+    }
+
+    #[should_panic(expected = "ApiError::User(10) [65546]")]
+    #[test]
+    fn should_not_transfer_from_when_paused() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+
+        fixture.deposit(Sender(owner), U512::from(42));
+        fixture.approve(Key::from(spender), U256::from(42), Sender(owner));
+
+        fixture.pause(&["transfer", "approve"], Sender(owner));
+        fixture.transfer_from(
+            Key::from(owner),
+            Key::from(fixture.joe),
+            U256::from(42),
+            Sender(spender),
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_pause_without_role() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.pause(&["withdraw"], Sender(fixture.bob));
+    }
+
+    #[test]
+    fn should_upgrade_and_keep_state() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(1000));
+        fixture.approve(Key::from(fixture.bob), U256::from(100), sender);
+        assert_eq!(fixture.storage_version(), 10);
+
+        let contract_hash_before = fixture.contract_hash();
+        fixture.upgrade(sender);
+        assert_ne!(fixture.contract_hash(), contract_hash_before);
+
+        // balances, allowances and main purse are kept by the new version
+        assert_eq!(fixture.storage_version(), 10);
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(1000))
+        );
+        assert_eq!(
+            fixture.allowance(Key::from(fixture.ali), Key::from(fixture.bob)),
+            Some(U256::from(100))
+        );
+        assert_eq!(fixture.cspr_balance(), U512::from(1000));
+
+        fixture.withdraw(sender, U512::from(400));
+        assert_eq!(fixture.cspr_balance(), U512::from(600));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(600))
+        );
+    }
+
+    #[test]
+    fn should_migrate_storage_from_version_1() {
+        let mut fixture = TestFixture::install_contract_v1(U512::from(1000));
+        let sender = Sender(fixture.ali);
+        assert_eq!(fixture.storage_version(), 1);
+        assert!(!fixture.has_contract_key("nonces"));
+
+        fixture.upgrade(sender);
+        assert_eq!(fixture.storage_version(), 10);
+
+        // named keys and dictionaries of every migration step
+        for name in &[
+            "flash_fee_bps",
+            "max_flash_loan",
+            "flash_loan_paused",
+            "__events_schema",
+            "nonces",
+            "chain_name",
+            "unwrap_self_transfers",
+            "deposit_fee_bps",
+            "withdraw_fee_bps",
+            "treasury",
+            "account_snapshots",
+            "total_supply_snapshots",
+            "current_snapshot_id",
+            "delegates",
+            "vote_checkpoints",
+            "vote_checkpoint_counts",
+            "freezer_role",
+            "frozen",
+            "withdrawal_window",
+            "withdrawal_limit",
+            "withdrawal_pause_floor",
+            "withdrawn_in_window",
+            "withdrawn_updated_at",
+            "address_tiers",
+            "tier_caps",
+            "allowlist_enabled",
+        ] {
+            assert!(fixture.has_contract_key(name), "{} is not migrated", name);
+        }
+        assert_eq!(fixture.fee_config(), (0, 0, fixture.ali));
+        assert!(fixture.has_role("freezer", Key::from(fixture.ali)));
+        assert!(!fixture.is_paused("flash_loan"));
+
+        // state of version 1 is kept
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(1000))
+        );
+        assert_eq!(fixture.token_total_supply(), U256::from(1000));
+        assert_eq!(fixture.cspr_balance(), U512::from(1000));
+
+        // migrated storage is used by entry points added since version 1
+        fixture.snapshot(sender);
+        fixture.transfer(Key::from(fixture.bob), U256::from(400), sender);
+        assert_eq!(
+            fixture.balance_of_at(Key::from(fixture.ali), 1),
+            U256::from(1000)
+        );
+        fixture.withdraw(sender, U512::from(600));
+        assert_eq!(fixture.token_total_supply(), U256::from(400));
+        assert_eq!(fixture.cspr_balance(), U512::from(400));
+    }
+
+    #[test]
+    fn should_transfer() {
+        let mut fixture = TestFixture::install_contract();
+
+        // Deposit WCPSR to ali balance
+        let cspr_deposit_amount = U512::from(1000);
+        let deposited_wcspr = U256::from(1000);
+        let sender = Sender(fixture.ali);
+
+        let initial_balance = fixture.balance_of(Key::from(fixture.ali)).unwrap();
+        let expected_balance = initial_balance + deposited_wcspr;
+
+        fixture.deposit(sender, cspr_deposit_amount);
+
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(expected_balance)
+        );
+        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+
+        // Make transfer from ali to bob
+        assert_eq!(fixture.balance_of(Key::from(fixture.bob)), None);
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(fixture.token_total_supply())
+        );
+        let transfer_amount_1 = U256::from(42);
+        fixture.transfer(
+            Key::from(fixture.bob),
+            transfer_amount_1,
+            Sender(fixture.ali),
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.bob)),
+            Some(transfer_amount_1)
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(fixture.token_total_supply() - transfer_amount_1)
+        );
+
+        // Make transfer from bob to ali
+        let transfer_amount_2 = U256::from(20);
+        fixture.transfer(
+            Key::from(fixture.ali),
+            transfer_amount_2,
+            Sender(fixture.bob),
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(fixture.token_total_supply() - transfer_amount_1 + transfer_amount_2),
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.bob)),
+            Some(transfer_amount_1 - transfer_amount_2)
+        );
+    }
+
+    #[test]
+    fn should_transfer_from() {
+        let approve_amount = U256::from(100);
+        let transfer_amount = U256::from(42);
+        assert!(approve_amount > transfer_amount);
+
+        let mut fixture = TestFixture::install_contract();
+
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+        let recipient = fixture.joe;
+
+        // Deposit WCPSR to ali balance
+        let cspr_deposit_amount = U512::from(1000);
+        let deposited_wcspr = U256::from(1000);
+        let sender = Sender(fixture.ali);
+
+        let initial_balance = fixture.balance_of(Key::from(fixture.ali)).unwrap();
+        let expected_balance = initial_balance + deposited_wcspr;
+
+        fixture.deposit(sender, cspr_deposit_amount);
+
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(expected_balance)
+        );
+        assert_eq!(fixture.cspr_balance(), cspr_deposit_amount);
+
+        // Approve ali WCSPR to be spent by bob
+        let owner_balance_before = fixture
+            .balance_of(Key::from(owner))
+            .expect("owner should have balance");
+        fixture.approve(Key::from(spender), approve_amount, Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), Key::from(spender)),
+            Some(approve_amount)
+        );
+
+        // Bob transfer WCSPR of ali to joe
+        fixture.transfer_from(
+            Key::from(owner),
+            Key::from(recipient),
+            transfer_amount,
+            Sender(spender),
+        );
+
+        assert_eq!(
+            fixture.balance_of(Key::from(owner)),
+            Some(owner_balance_before - transfer_amount),
+            "should decrease balance of the owner"
+        );
+        assert_eq!(
+            fixture.allowance(Key::from(owner), Key::from(spender)),
+            Some(approve_amount - transfer_amount),
+            "should decrease allowance of the spender"
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(recipient)),
+            Some(transfer_amount),
+            "recipient should receive tokens"
+        );
     }
 
     #[test]
@@ -358,6 +904,1182 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_emit_deposit_and_withdrawal_events() {
+        let mut fixture = TestFixture::install_contract();
+        assert_eq!(fixture.events_length(), 0);
+
+        let sender = Sender(fixture.ali);
+        fixture.deposit(sender, U512::from(42));
+        fixture.withdraw(sender, U512::from(12));
+
+        assert_eq!(fixture.events_length(), 2);
+        assert_eq!(
+            fixture.event(0),
+            Event::Deposit {
+                owner: Key::from(fixture.ali),
+                amount: U256::from(42),
+                fee: U256::zero()
+            }
+        );
+        assert_eq!(
+            fixture.event(1),
+            Event::Withdrawal {
+                owner: Key::from(fixture.ali),
+                amount: U256::from(12),
+                fee: U256::zero()
+            }
+        );
+    }
+
+    #[test]
+    fn should_emit_transfer_and_approval_events() {
+        let mut fixture = TestFixture::install_contract();
+
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+        let recipient = fixture.joe;
+
+        fixture.deposit(Sender(owner), U512::from(1000));
+
+        fixture.transfer(Key::from(spender), U256::from(10), Sender(owner));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Transfer {
+                sender: Key::from(owner),
+                recipient: Key::from(spender),
+                amount: U256::from(10)
+            }
+        );
+
+        fixture.approve(Key::from(spender), U256::from(100), Sender(owner));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Approval {
+                owner: Key::from(owner),
+                spender: Key::from(spender),
+                amount: U256::from(100)
+            }
+        );
+
+        fixture.transfer_from(
+            Key::from(owner),
+            Key::from(recipient),
+            U256::from(42),
+            Sender(spender),
+        );
+        assert_eq!(
+            fixture.last_event(),
+            Event::Transfer {
+                sender: Key::from(owner),
+                recipient: Key::from(recipient),
+                amount: U256::from(42)
+            }
+        );
+        assert_eq!(fixture.events_length(), 4);
+    }
+
+    #[test]
+    fn should_flash_loan() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+        let admin = Sender(fixture.ali);
+
+        // 1% fee
+        fixture.set_flash_loan_config(100, U256::from(1_000_000), admin);
+        assert_eq!(fixture.flash_fee_bps(), 100);
+        assert_eq!(fixture.max_flash_loan(), U256::from(1_000_000));
+
+        // borrower holds enough WCSPR to pay the fee
+        fixture.deposit_for(borrower, U512::from(10), admin);
+
+        fixture.flash_loan(borrower, U256::from(1000), Sender(fixture.bob));
+
+        assert_eq!(fixture.test_flash_borrower_last_loan(), U256::from(1000));
+        assert_eq!(fixture.balance_of(borrower), Some(U256::zero()));
+        assert_eq!(fixture.token_total_supply(), U256::zero());
+        // paid fee stays in the contract as excess reserves
+        assert_eq!(fixture.cspr_balance(), U512::from(10));
+        assert_eq!(
+            fixture.last_event(),
+            Event::FlashLoan {
+                receiver: borrower,
+                amount: U256::from(1000),
+                fee: U256::from(10)
+            }
+        );
+    }
+
+    #[test]
+    fn should_keep_balances_at_snapshots() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+        let ali = Key::from(fixture.ali);
+        let bob = Key::from(fixture.bob);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.snapshot(admin);
+        assert_eq!(fixture.current_snapshot_id(), 1);
+
+        fixture.transfer(bob, U256::from(300), admin);
+        fixture.snapshot(admin);
+        fixture.withdraw(admin, U512::from(200));
+        fixture.deposit(Sender(fixture.bob), U512::from(50));
+
+        assert_eq!(fixture.balance_of_at(ali, 1), U256::from(1000));
+        assert_eq!(fixture.balance_of_at(bob, 1), U256::zero());
+        assert_eq!(fixture.total_supply_at(1), U256::from(1000));
+
+        assert_eq!(fixture.balance_of_at(ali, 2), U256::from(700));
+        assert_eq!(fixture.balance_of_at(bob, 2), U256::from(300));
+        assert_eq!(fixture.total_supply_at(2), U256::from(1000));
+
+        assert_eq!(fixture.balance_of(ali), Some(U256::from(500)));
+        assert_eq!(fixture.balance_of(bob), Some(U256::from(350)));
+        assert_eq!(fixture.token_total_supply(), U256::from(850));
+    }
+
+    #[test]
+    fn should_not_get_balance_at_unknown_snapshot() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+        let ali = Key::from(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.snapshot(admin);
+        assert_eq!(fixture.balance_of_at(ali, 1), U256::from(1000));
+
+        // ids start from 1 and the next snapshot is not taken yet
+        for snapshot_id in &[0, 2] {
+            let error = fixture.try_balance_of_at(ali, *snapshot_id).unwrap_err();
+            assert!(error.contains("ApiError::User(23) [65559]"), "{}", error);
+        }
+    }
+
+    #[should_panic(expected = "ApiError::User(23) [65559]")]
+    #[test]
+    fn should_not_get_total_supply_at_future_snapshot() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.snapshot(admin);
+        fixture.total_supply_at(2);
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_snapshot_without_role() {
+        let mut fixture = TestFixture::install_contract();
+        let bob = fixture.bob;
+
+        fixture.snapshot(Sender(bob));
+    }
+
+    #[test]
+    fn should_delegate_votes() {
+        let mut fixture = TestFixture::install_contract();
+        let ali = Key::from(fixture.ali);
+        let bob = Key::from(fixture.bob);
+        let joe = Key::from(fixture.joe);
+
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        assert_eq!(fixture.delegates(ali), None);
+        assert_eq!(fixture.get_votes(ali), U256::zero());
+
+        // tokens stay with ali, votes move to joe
+        fixture.delegate(joe, Sender(fixture.ali));
+        assert_eq!(fixture.delegates(ali), Some(joe));
+        assert_eq!(fixture.get_votes(joe), U256::from(1000));
+        assert_eq!(fixture.balance_of(ali), Some(U256::from(1000)));
+
+        // transfer to a holder without delegatee removes votes
+        fixture.transfer(bob, U256::from(300), Sender(fixture.ali));
+        assert_eq!(fixture.get_votes(joe), U256::from(700));
+
+        // bob delegates to himself, then mint and burn move his votes
+        fixture.delegate(bob, Sender(fixture.bob));
+        assert_eq!(fixture.get_votes(bob), U256::from(300));
+        fixture.deposit(Sender(fixture.bob), U512::from(50));
+        assert_eq!(fixture.get_votes(bob), U256::from(350));
+        fixture.withdraw(Sender(fixture.bob), U512::from(100));
+        assert_eq!(fixture.get_votes(bob), U256::from(250));
+
+        // redelegation moves the whole balance
+        fixture.delegate(bob, Sender(fixture.ali));
+        assert_eq!(fixture.get_votes(joe), U256::zero());
+        assert_eq!(fixture.get_votes(bob), U256::from(950));
+    }
+
+    #[test]
+    fn should_get_past_votes_at_checkpoint_boundaries() {
+        let mut fixture = TestFixture::install_contract();
+        let ali = Key::from(fixture.ali);
+        let bob = Key::from(fixture.bob);
+        let joe = Key::from(fixture.joe);
+
+        fixture.set_block_time(1000);
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        fixture.delegate(joe, Sender(fixture.ali));
+
+        fixture.set_block_time(2000);
+        fixture.transfer(bob, U256::from(300), Sender(fixture.ali));
+
+        fixture.set_block_time(3000);
+        assert_eq!(fixture.get_past_votes(joe, 999), U256::zero());
+        assert_eq!(fixture.get_past_votes(joe, 1000), U256::from(1000));
+        assert_eq!(fixture.get_past_votes(joe, 1999), U256::from(1000));
+        assert_eq!(fixture.get_past_votes(joe, 2000), U256::from(700));
+        assert_eq!(fixture.get_past_votes(joe, 2999), U256::from(700));
+        assert_eq!(fixture.get_votes(joe), U256::from(700));
+        assert_eq!(fixture.get_past_votes(ali, 2000), U256::zero());
+
+        // current and future block times are not in the past yet
+        for timestamp in &[3000, 3001] {
+            let error = fixture.try_get_past_votes(joe, *timestamp).unwrap_err();
+            assert!(error.contains("ApiError::User(24) [65560]"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn should_delegate_votes_back_to_self() {
+        let mut fixture = TestFixture::install_contract();
+        let ali = Key::from(fixture.ali);
+        let joe = Key::from(fixture.joe);
+
+        fixture.set_block_time(1000);
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        fixture.delegate(joe, Sender(fixture.ali));
+
+        fixture.set_block_time(2000);
+        fixture.delegate(ali, Sender(fixture.ali));
+        assert_eq!(fixture.delegates(ali), Some(ali));
+        assert_eq!(fixture.get_votes(ali), U256::from(1000));
+        assert_eq!(fixture.get_votes(joe), U256::zero());
+
+        fixture.set_block_time(3000);
+        assert_eq!(fixture.get_past_votes(joe, 1500), U256::from(1000));
+        assert_eq!(fixture.get_past_votes(joe, 2000), U256::zero());
+        assert_eq!(fixture.get_past_votes(ali, 1500), U256::zero());
+        assert_eq!(fixture.get_past_votes(ali, 2000), U256::from(1000));
+    }
+
+    #[test]
+    fn should_not_move_votes_on_transfer_without_delegation() {
+        let mut fixture = TestFixture::install_contract();
+        let ali = Key::from(fixture.ali);
+        let bob = Key::from(fixture.bob);
+        let joe = Key::from(fixture.joe);
+
+        fixture.set_block_time(1000);
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        fixture.deposit(Sender(fixture.bob), U512::from(50));
+
+        // neither holder delegated, so nobody gets votes
+        fixture.set_block_time(2000);
+        fixture.transfer(bob, U256::from(300), Sender(fixture.ali));
+        assert_eq!(fixture.get_votes(ali), U256::zero());
+        assert_eq!(fixture.get_votes(bob), U256::zero());
+
+        // zero amount transfer of a delegator keeps votes of its delegatee
+        fixture.delegate(joe, Sender(fixture.ali));
+        fixture.set_block_time(3000);
+        fixture.transfer(bob, U256::zero(), Sender(fixture.ali));
+        assert_eq!(fixture.get_votes(joe), U256::from(700));
+
+        fixture.set_block_time(4000);
+        assert_eq!(fixture.get_past_votes(joe, 3000), U256::from(700));
+        assert_eq!(fixture.get_past_votes(bob, 3000), U256::zero());
+    }
+
+    #[test]
+    fn should_freeze_and_unfreeze() {
+        let mut fixture = TestFixture::install_contract();
+        let freezer = Sender(fixture.ali);
+        let bob = Key::from(fixture.bob);
+
+        fixture.deposit(Sender(fixture.bob), U512::from(100));
+
+        fixture.freeze(bob, freezer);
+        assert!(fixture.is_frozen(bob));
+        assert_eq!(fixture.last_event(), Event::Frozen { address: bob });
+
+        fixture.unfreeze(bob, freezer);
+        assert!(!fixture.is_frozen(bob));
+        assert_eq!(fixture.last_event(), Event::Unfrozen { address: bob });
+
+        fixture.transfer(Key::from(fixture.joe), U256::from(10), Sender(fixture.bob));
+        assert_eq!(fixture.balance_of(bob), Some(U256::from(90)));
+    }
+
+    #[should_panic(expected = "ApiError::User(25) [65561]")]
+    #[test]
+    fn should_not_transfer_from_frozen_address() {
+        let mut fixture = TestFixture::install_contract();
+        let bob = Key::from(fixture.bob);
+
+        fixture.deposit(Sender(fixture.bob), U512::from(100));
+        fixture.freeze(bob, Sender(fixture.ali));
+        fixture.transfer(Key::from(fixture.joe), U256::from(10), Sender(fixture.bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(25) [65561]")]
+    #[test]
+    fn should_not_transfer_from_to_frozen_recipient() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+        let joe = Key::from(fixture.joe);
+
+        fixture.deposit(Sender(owner), U512::from(100));
+        fixture.approve(Key::from(spender), U256::from(100), Sender(owner));
+        fixture.freeze(joe, Sender(owner));
+        fixture.transfer_from(Key::from(owner), joe, U256::from(10), Sender(spender));
+    }
+
+    #[should_panic(expected = "ApiError::User(25) [65561]")]
+    #[test]
+    fn should_not_deposit_to_frozen_address() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.freeze(Key::from(fixture.bob), Sender(fixture.ali));
+        fixture.deposit(Sender(fixture.bob), U512::from(100));
+    }
+
+    #[should_panic(expected = "ApiError::User(25) [65561]")]
+    #[test]
+    fn should_not_withdraw_from_frozen_address() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.deposit(Sender(fixture.bob), U512::from(100));
+        fixture.freeze(Key::from(fixture.bob), Sender(fixture.ali));
+        fixture.withdraw(Sender(fixture.bob), U512::from(100));
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_freeze_without_role() {
+        let mut fixture = TestFixture::install_contract();
+        let ali = Key::from(fixture.ali);
+
+        fixture.freeze(ali, Sender(fixture.bob));
+    }
+
+    // One era of 2 hours
+    const WITHDRAWAL_WINDOW: u64 = 2 * 60 * 60 * 1000;
+
+    #[test]
+    fn should_withdraw_within_withdrawal_limit() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.set_withdrawal_limit(WITHDRAWAL_WINDOW, U512::from(500), U512::zero(), admin);
+        assert_eq!(fixture.withdrawal_capacity(), U512::from(500));
+
+        fixture.withdraw(admin, U512::from(300));
+        fixture.withdraw_to(fixture.joe, U512::from(200), admin);
+        assert_eq!(fixture.withdrawal_capacity(), U512::zero());
+        assert_eq!(fixture.cspr_balance(), U512::from(500));
+    }
+
+    #[should_panic(expected = "ApiError::User(26) [65562]")]
+    #[test]
+    fn should_not_withdraw_over_withdrawal_limit() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.set_withdrawal_limit(WITHDRAWAL_WINDOW, U512::from(500), U512::zero(), admin);
+
+        fixture.withdraw(admin, U512::from(300));
+        fixture.withdraw(admin, U512::from(300));
+    }
+
+    #[test]
+    fn should_not_withdraw_twice_the_limit_around_window_boundary() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.set_withdrawal_limit(WITHDRAWAL_WINDOW, U512::from(500), U512::zero(), admin);
+
+        fixture.set_block_time(WITHDRAWAL_WINDOW - 1);
+        fixture.withdraw(admin, U512::from(500));
+
+        fixture.set_block_time(WITHDRAWAL_WINDOW);
+        assert_eq!(fixture.withdrawal_capacity(), U512::zero());
+        let error = fixture.try_withdraw(admin, U512::from(500)).unwrap_err();
+        assert!(error.contains("ApiError::User(26) [65562]"), "{}", error);
+        assert_eq!(fixture.cspr_balance(), U512::from(500));
+    }
+
+    #[test]
+    fn should_refill_withdrawal_capacity_over_window() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.set_withdrawal_limit(WITHDRAWAL_WINDOW, U512::from(500), U512::zero(), admin);
+        fixture.withdraw(admin, U512::from(500));
+
+        // half of the window refills half of the limit
+        fixture.set_block_time(WITHDRAWAL_WINDOW / 2);
+        assert_eq!(fixture.withdrawal_capacity(), U512::from(250));
+        fixture.withdraw(admin, U512::from(250));
+        let error = fixture.try_withdraw(admin, U512::from(1)).unwrap_err();
+        assert!(error.contains("ApiError::User(26) [65562]"), "{}", error);
+
+        // capacity is never above the limit
+        fixture.set_block_time(WITHDRAWAL_WINDOW / 2 + 2 * WITHDRAWAL_WINDOW);
+        assert_eq!(fixture.withdrawal_capacity(), U512::from(500));
+        fixture.withdraw(admin, U512::from(250));
+        assert_eq!(fixture.withdrawal_capacity(), U512::from(250));
+        assert_eq!(fixture.cspr_balance(), U512::zero());
+    }
+
+    #[test]
+    fn should_pause_withdrawals_below_pause_floor() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(1000));
+        fixture.set_withdrawal_limit(WITHDRAWAL_WINDOW, U512::from(500), U512::from(200), admin);
+
+        fixture.withdraw(admin, U512::from(300));
+        assert!(!fixture.is_paused("withdraw"));
+
+        // withdrawal over the capacity is reverted, so it does not pause withdrawals
+        let error = fixture.try_withdraw(admin, U512::from(250)).unwrap_err();
+        assert!(error.contains("ApiError::User(26) [65562]"), "{}", error);
+        assert!(!fixture.is_paused("withdraw"));
+
+        // withdrawal that leaves capacity below the floor pauses withdrawals
+        fixture.withdraw(admin, U512::from(1));
+        assert!(fixture.is_paused("withdraw"));
+        assert_eq!(
+            fixture.last_event(),
+            Event::WithdrawalsAutoPaused {
+                capacity: U256::from(199)
+            }
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(699))
+        );
+        assert_eq!(fixture.cspr_balance(), U512::from(699));
+
+        // withdrawals stay paused once the capacity is refilled
+        fixture.set_block_time(WITHDRAWAL_WINDOW);
+        let error = fixture.try_withdraw(admin, U512::from(100)).unwrap_err();
+        assert!(error.contains("ApiError::User(10) [65546]"), "{}", error);
+
+        fixture.unpause(&["withdraw"], admin);
+        fixture.withdraw(admin, U512::from(100));
+        assert_eq!(fixture.withdrawal_capacity(), U512::from(400));
+    }
+
+    #[should_panic(expected = "ApiError::User(32) [65568]")]
+    #[test]
+    fn should_not_set_withdrawal_limit_without_window() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.set_withdrawal_limit(0, U512::from(500), U512::zero(), Sender(fixture.ali));
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_set_withdrawal_limit_without_role() {
+        let mut fixture = TestFixture::install_contract();
+
+        fixture.set_withdrawal_limit(
+            WITHDRAWAL_WINDOW,
+            U512::from(500),
+            U512::zero(),
+            Sender(fixture.bob),
+        );
+    }
+
+    #[test]
+    fn should_take_deposit_and_withdraw_fees() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+        let treasury = fixture.joe;
+        assert_eq!(fixture.fee_config(), (0, 0, fixture.ali));
+
+        // 1% deposit fee and 2% withdraw fee
+        fixture.set_fee_config(100, 200, treasury, admin);
+        assert_eq!(fixture.fee_config(), (100, 200, treasury));
+
+        fixture.deposit(admin, U512::from(1000));
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(990))
+        );
+        assert_eq!(fixture.account_cspr_balance(treasury), U512::from(10));
+        assert_eq!(fixture.cspr_balance(), U512::from(990));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Deposit {
+                owner: Key::from(fixture.ali),
+                amount: U256::from(990),
+                fee: U256::from(10)
+            }
+        );
+
+        // recipient does not pay for the deploy, so it receives exactly the payout
+        fixture.withdraw_to(fixture.dan, U512::from(500), admin);
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(490))
+        );
+        assert_eq!(fixture.account_cspr_balance(fixture.dan), U512::from(490));
+        assert_eq!(fixture.account_cspr_balance(treasury), U512::from(20));
+        assert_eq!(fixture.cspr_balance(), U512::from(490));
+        assert_eq!(fixture.token_total_supply(), U256::from(490));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Withdrawal {
+                owner: Key::from(fixture.ali),
+                amount: U256::from(500),
+                fee: U256::from(10)
+            }
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(16) [65552]")]
+    #[test]
+    fn should_not_set_fee_above_cap() {
+        let mut fixture = TestFixture::install_contract();
+        let treasury = fixture.joe;
+
+        fixture.set_fee_config(0, 501, treasury, Sender(fixture.ali));
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_set_fee_config_without_role() {
+        let mut fixture = TestFixture::install_contract();
+        let bob = fixture.bob;
+
+        fixture.set_fee_config(100, 100, bob, Sender(bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(15) [65551]")]
+    #[test]
+    fn should_not_install_with_initial_supply() {
+        TestFixture::install_contract_with_initial_supply(U256::from(1000));
+    }
+
+    #[test]
+    fn should_sync_cspr_balance() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(1000));
+        fixture.sync(Sender(fixture.bob));
+        assert_eq!(fixture.cspr_balance(), U512::from(1000));
+    }
+
+    #[test]
+    fn should_skim_excess_reserves() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+        let admin = Sender(fixture.ali);
+
+        // paid flash loan fee stays in the main purse as excess reserves
+        fixture.set_flash_loan_config(100, U256::from(1_000_000), admin);
+        fixture.deposit_for(borrower, U512::from(10), admin);
+        fixture.flash_loan(borrower, U256::from(1000), Sender(fixture.bob));
+        fixture.deposit(admin, U512::from(500));
+        assert_eq!(fixture.cspr_balance(), U512::from(510));
+
+        fixture.skim(fixture.joe, admin);
+
+        assert_eq!(fixture.account_cspr_balance(fixture.joe), U512::from(10));
+        assert_eq!(fixture.cspr_balance(), U512::from(500));
+        assert_eq!(fixture.token_total_supply(), U256::from(500));
+    }
+
+    #[should_panic(expected = "ApiError::User(5) [65541]")]
+    #[test]
+    fn should_not_skim_without_excess_reserves() {
+        let mut fixture = TestFixture::install_contract();
+        let admin = Sender(fixture.ali);
+
+        fixture.deposit(admin, U512::from(500));
+        fixture.skim(fixture.joe, admin);
+    }
+
+    #[should_panic(expected = "ApiError::User(8) [65544]")]
+    #[test]
+    fn should_not_skim_without_role() {
+        let mut fixture = TestFixture::install_contract();
+        let bob = fixture.bob;
+
+        fixture.skim(bob, Sender(bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(14) [65550]")]
+    #[test]
+    fn should_not_flash_loan_without_repaying_fee() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+
+        fixture.set_flash_loan_config(100, U256::from(1_000_000), Sender(fixture.ali));
+
+        fixture.flash_loan(borrower, U256::from(1000), Sender(fixture.bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(6) [65542]")]
+    #[test]
+    fn should_not_flash_loan_overflowing_amount() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+
+        fixture.set_flash_loan_config(100, U256::MAX, Sender(fixture.ali));
+        fixture.flash_loan(borrower, U256::MAX, Sender(fixture.bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(25) [65561]")]
+    #[test]
+    fn should_not_flash_loan_to_frozen_receiver() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+        let admin = Sender(fixture.ali);
+
+        fixture.set_flash_loan_config(0, U256::from(1_000_000), admin);
+        fixture.freeze(borrower, admin);
+        fixture.flash_loan(borrower, U256::from(1000), Sender(fixture.bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(13) [65549]")]
+    #[test]
+    fn should_not_flash_loan_more_than_max() {
+        let mut fixture = TestFixture::install_contract();
+        fixture.install_test_flash_borrower();
+        let borrower = fixture.test_flash_borrower();
+
+        fixture.flash_loan(borrower, U256::from(1), Sender(fixture.bob));
+    }
+
+    #[should_panic(expected = "ApiError::User(21) [65557]")]
+    #[test]
+    fn should_not_transfer_to_wcspr_contract() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(1000));
+        fixture.transfer(fixture.wcspr_package(), U256::from(400), sender);
+    }
+
+    #[test]
+    fn should_unwrap_transfer_to_wcspr_contract() {
+        let mut fixture = TestFixture::install_contract_unwrapping_self_transfers();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(1000));
+        fixture.transfer(fixture.wcspr_package(), U256::from(400), sender);
+
+        assert_eq!(fixture.balance_of(fixture.wcspr_package()), None);
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(600))
+        );
+        assert_eq!(fixture.token_total_supply(), U256::from(600));
+        assert_eq!(fixture.cspr_balance(), U512::from(600));
+        assert_eq!(
+            fixture.last_event(),
+            Event::Withdrawal {
+                owner: Key::from(fixture.ali),
+                amount: U256::from(400),
+                fee: U256::zero()
+            }
+        );
+    }
+
+    #[test]
+    fn should_unwrap_transfer_from_to_wcspr_contract() {
+        let mut fixture = TestFixture::install_contract_unwrapping_self_transfers();
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+
+        fixture.deposit(Sender(owner), U512::from(1000));
+        fixture.approve(Key::from(spender), U256::from(500), Sender(owner));
+
+        // spender pays for the deploy, so owner CSPR balance changes only by unwrapped amount
+        let owner_cspr_before = fixture.account_cspr_balance(owner);
+        fixture.transfer_from(
+            Key::from(owner),
+            fixture.wcspr_package(),
+            U256::from(400),
+            Sender(spender),
+        );
+
+        assert_eq!(
+            fixture.account_cspr_balance(owner),
+            owner_cspr_before + U512::from(400)
+        );
+        assert_eq!(
+            fixture.allowance(Key::from(owner), Key::from(spender)),
+            Some(U256::from(100))
+        );
+        assert_eq!(fixture.balance_of(Key::from(owner)), Some(U256::from(600)));
+        assert_eq!(fixture.cspr_balance(), U512::from(600));
+    }
+
+    #[test]
+    fn should_increase_and_decrease_allowance() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = Key::from(fixture.bob);
+
+        fixture.increase_allowance(spender, U256::from(100), Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), spender),
+            Some(U256::from(100))
+        );
+
+        fixture.increase_allowance(spender, U256::from(50), Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), spender),
+            Some(U256::from(150))
+        );
+
+        fixture.decrease_allowance(spender, U256::from(120), Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), spender),
+            Some(U256::from(30))
+        );
+        assert_eq!(
+            fixture.last_event(),
+            Event::Approval {
+                owner: Key::from(owner),
+                spender,
+                amount: U256::from(30)
+            }
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(19) [65555]")]
+    #[test]
+    fn should_not_decrease_allowance_below_zero() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = Key::from(fixture.bob);
+
+        fixture.approve(spender, U256::from(10), Sender(owner));
+        fixture.decrease_allowance(spender, U256::from(11), Sender(owner));
+    }
+
+    #[test]
+    fn should_approve_if_current_allowance_matches() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = Key::from(fixture.bob);
+
+        fixture.approve_if(spender, U256::zero(), U256::from(100), Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), spender),
+            Some(U256::from(100))
+        );
+
+        fixture.approve_if(spender, U256::from(100), U256::from(40), Sender(owner));
+        assert_eq!(
+            fixture.allowance(Key::from(owner), spender),
+            Some(U256::from(40))
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(20) [65556]")]
+    #[test]
+    fn should_not_approve_if_allowance_was_spent() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+
+        fixture.deposit(Sender(owner), U512::from(1000));
+        fixture.approve(Key::from(spender), U256::from(100), Sender(owner));
+
+        // spender front-runs the allowance change
+        fixture.transfer_from(
+            Key::from(owner),
+            Key::from(spender),
+            U256::from(100),
+            Sender(spender),
+        );
+        assert_eq!(
+            fixture.allowance(Key::from(owner), Key::from(spender)),
+            Some(U256::zero())
+        );
+
+        fixture.approve_if(
+            Key::from(spender),
+            U256::from(100),
+            U256::from(50),
+            Sender(owner),
+        );
+    }
+
+    #[test]
+    fn should_permit() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = fixture.bob;
+
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        fixture.transfer(
+            Key::from(fixture.dan),
+            U256::from(1000),
+            Sender(fixture.ali),
+        );
+        assert_eq!(fixture.nonce(&owner), 0);
+
+        let signature = fixture.sign_permit(Key::from(spender), U256::from(100), 0, u64::MAX);
+        // spender submits the permit, owner does not need to pay for a deploy
+        fixture.permit(
+            owner.clone(),
+            Key::from(spender),
+            U256::from(100),
+            u64::MAX,
+            signature,
+            Sender(spender),
+        );
+
+        assert_eq!(fixture.nonce(&owner), 1);
+        assert_eq!(
+            fixture.allowance(Key::from(fixture.dan), Key::from(spender)),
+            Some(U256::from(100))
+        );
+        assert_eq!(
+            fixture.last_event(),
+            Event::Approval {
+                owner: Key::from(fixture.dan),
+                spender: Key::from(spender),
+                amount: U256::from(100)
+            }
+        );
+
+        fixture.transfer_from(
+            Key::from(fixture.dan),
+            Key::from(spender),
+            U256::from(100),
+            Sender(spender),
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(spender)),
+            Some(U256::from(100))
+        );
+    }
+
+    #[test]
+    fn should_permit_with_secp256k1_key() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.eve_key.clone();
+        let spender = fixture.bob;
+
+        fixture.deposit(Sender(fixture.ali), U512::from(1000));
+        fixture.transfer(
+            Key::from(fixture.eve),
+            U256::from(1000),
+            Sender(fixture.ali),
+        );
+
+        let signature =
+            fixture.sign_secp256k1_permit(Key::from(spender), U256::from(100), 0, u64::MAX);
+        fixture.permit(
+            owner.clone(),
+            Key::from(spender),
+            U256::from(100),
+            u64::MAX,
+            signature,
+            Sender(spender),
+        );
+
+        assert_eq!(fixture.nonce(&owner), 1);
+        assert_eq!(
+            fixture.allowance(Key::from(fixture.eve), Key::from(spender)),
+            Some(U256::from(100))
+        );
+
+        fixture.transfer_from(
+            Key::from(fixture.eve),
+            Key::from(spender),
+            U256::from(100),
+            Sender(spender),
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(spender)),
+            Some(U256::from(100))
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(18) [65554]")]
+    #[test]
+    fn should_not_replay_permit() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, u64::MAX);
+        fixture.permit(
+            owner.clone(),
+            spender,
+            U256::from(100),
+            u64::MAX,
+            signature.clone(),
+            Sender(fixture.bob),
+        );
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(100),
+            u64::MAX,
+            signature,
+            Sender(fixture.bob),
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(18) [65554]")]
+    #[test]
+    fn should_not_permit_other_amount_than_signed() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, u64::MAX);
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(1000),
+            u64::MAX,
+            signature,
+            Sender(fixture.bob),
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(17) [65553]")]
+    #[test]
+    fn should_not_permit_after_deadline() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let deadline = 1_000;
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, deadline);
+        fixture.set_block_time(deadline + 1);
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(100),
+            deadline,
+            signature,
+            Sender(fixture.bob),
+        );
+    }
+
+    #[test]
+    fn should_permit_at_deadline() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let deadline = 1_000;
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, deadline);
+        fixture.set_block_time(deadline);
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(100),
+            deadline,
+            signature,
+            Sender(fixture.bob),
+        );
+
+        assert_eq!(
+            fixture.allowance(Key::from(fixture.dan), spender),
+            Some(U256::from(100))
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(18) [65554]")]
+    #[test]
+    fn should_not_permit_with_trailing_signature_bytes() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, u64::MAX);
+        let mut signature = signature.to_vec();
+        signature.push(0);
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(100),
+            u64::MAX,
+            Bytes::from(signature),
+            Sender(fixture.bob),
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(18) [65554]")]
+    #[test]
+    fn should_not_permit_with_malformed_signature() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.dan_key.clone();
+        let spender = Key::from(fixture.bob);
+
+        let signature = fixture.sign_permit(spender, U256::from(100), 0, u64::MAX);
+        let mut signature = signature.to_vec();
+        signature.truncate(signature.len() - 1);
+        fixture.permit(
+            owner,
+            spender,
+            U256::from(100),
+            u64::MAX,
+            Bytes::from(signature),
+            Sender(fixture.bob),
+        );
+    }
+
+    #[test]
+    fn should_batch_transfer() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+        let recipients = [
+            (Key::from(fixture.bob), U256::from(10)),
+            (Key::from(fixture.joe), U256::from(20)),
+            (Key::from(fixture.dan), U256::from(30)),
+        ];
+
+        fixture.deposit(sender, U512::from(1000));
+        let events_before = fixture.events_length();
+        fixture.batch_transfer(&recipients, sender);
+
+        for (recipient, amount) in recipients.iter() {
+            assert_eq!(fixture.balance_of(*recipient), Some(*amount));
+        }
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(940))
+        );
+
+        // one event per leg
+        assert_eq!(fixture.events_length(), events_before + 3);
+        assert_eq!(
+            fixture.last_event(),
+            Event::Transfer {
+                sender: Key::from(fixture.ali),
+                recipient: Key::from(fixture.dan),
+                amount: U256::from(30)
+            }
+        );
+    }
+
+    #[test]
+    fn should_batch_transfer_from() {
+        let mut fixture = TestFixture::install_contract();
+        let owner = fixture.ali;
+        let spender = fixture.bob;
+        let recipients = [
+            (Key::from(fixture.joe), U256::from(20)),
+            (Key::from(fixture.dan), U256::from(30)),
+        ];
+
+        fixture.deposit(Sender(owner), U512::from(1000));
+        fixture.approve(Key::from(spender), U256::from(100), Sender(owner));
+        fixture.batch_transfer_from(Key::from(owner), &recipients, Sender(spender));
+
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.joe)),
+            Some(U256::from(20))
+        );
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.dan)),
+            Some(U256::from(30))
+        );
+        assert_eq!(
+            fixture.allowance(Key::from(owner), Key::from(spender)),
+            Some(U256::from(50))
+        );
+    }
+
+    #[test]
+    fn should_not_batch_transfer_partially() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(100));
+        // second leg exceeds the balance, so the first one is reverted as well
+        let error = fixture
+            .try_batch_transfer(
+                &[
+                    (Key::from(fixture.bob), U256::from(60)),
+                    (Key::from(fixture.joe), U256::from(60)),
+                ],
+                sender,
+            )
+            .unwrap_err();
+
+        assert!(
+            error.contains("ApiError::User(65534) [131070]"),
+            "{}",
+            error
+        );
+        assert_eq!(fixture.balance_of(Key::from(fixture.bob)), None);
+        assert_eq!(fixture.balance_of(Key::from(fixture.joe)), None);
+        assert_eq!(
+            fixture.balance_of(Key::from(fixture.ali)),
+            Some(U256::from(100))
+        );
+    }
+
+    #[test]
+    fn should_batch_transfer_cheaper_than_single_transfers() {
+        let mut fixture = GasFixture::install_contract();
+        fixture.deposit(U512::from(1000));
+
+        // different fresh recipients, so both ways create the same number of balances
+        let recipients = |seed: u8| -> Vec<(Key, U256)> {
+            (0..10u8)
+                .map(|i| {
+                    let recipient = AccountHash::new([seed + i; 32]);
+                    (Key::from(recipient), U256::from(10))
+                })
+                .collect()
+        };
+
+        let single_transfers_gas = recipients(100)
+            .into_iter()
+            .fold(U512::zero(), |gas, (recipient, amount)| {
+                gas + fixture.transfer(recipient, amount)
+            });
+        let batch_transfer_gas = fixture.batch_transfer(&recipients(200));
+
+        assert!(
+            batch_transfer_gas < single_transfers_gas,
+            "batch of 10 costs {}, 10 single transfers cost {}",
+            batch_transfer_gas,
+            single_transfers_gas
+        );
+    }
+
+    #[should_panic(expected = "ApiError::User(22) [65558]")]
+    #[test]
+    fn should_not_batch_transfer_more_than_max_batch_size() {
+        let mut fixture = TestFixture::install_contract();
+        let sender = Sender(fixture.ali);
+
+        fixture.deposit(sender, U512::from(1000));
+        let recipients = vec![(Key::from(fixture.bob), U256::one()); 101];
+        fixture.batch_transfer(&recipients, sender);
+    }
+
     #[should_panic(expected = "ApiError::User(65534) [131070]")]
     #[test]
     fn should_not_transfer_with_insufficient_balance() {
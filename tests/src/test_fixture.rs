@@ -2,18 +2,39 @@ use blake2::{
     digest::{Update, VariableOutput},
     VarBlake2b,
 };
-use casper_engine_test_support::{Code, SessionBuilder, TestContext, TestContextBuilder};
+use std::panic::{self, AssertUnwindSafe};
+
+use casper_engine_test_support::{
+    internal::{ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_RUN_GENESIS_REQUEST},
+    Code, Session, SessionBuilder, TestContext, TestContextBuilder, DEFAULT_ACCOUNT_ADDR,
+};
 use casper_erc20::constants as consts;
 use casper_types::{
     account::AccountHash,
-    bytesrepr::{FromBytes, ToBytes},
-    runtime_args, AsymmetricType, CLTyped, ContractHash, Key, PublicKey, RuntimeArgs, U256, U512,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    crypto, runtime_args, AsymmetricType, CLTyped, ContractHash, ContractPackageHash, Key,
+    PublicKey, RuntimeArgs, SecretKey, U256, U512,
 };
 
 const CONTRACT_WCSPR_TOKEN: &str = "wcspr.wasm";
 const CONTRACT_KEY_NAME: &str = "wcspr_token";
+const CONTRACT_PACKAGE_KEY_NAME: &str = "wcspr_token_package_hash";
 
 const CONTRACT_PRE_DEPOSIT: &str = "pre_deposit.wasm";
+const CONTRACT_PRE_DEPOSIT_FOR: &str = "pre_deposit_for.wasm";
+const CONTRACT_WRAP_AND_TRANSFER: &str = "wrap_and_transfer.wasm";
+const CONTRACT_WRAP_AND_APPROVE: &str = "wrap_and_approve.wasm";
+
+const CONTRACT_TEST_VAULT: &str = "test_vault.wasm";
+const TEST_VAULT_KEY_NAME: &str = "test_vault";
+const TEST_VAULT_PACKAGE_KEY_NAME: &str = "test_vault_package_hash";
+
+const CONTRACT_TEST_FLASH_BORROWER: &str = "test_flash_borrower.wasm";
+const TEST_FLASH_BORROWER_KEY_NAME: &str = "test_flash_borrower";
+const TEST_FLASH_BORROWER_PACKAGE_KEY_NAME: &str = "test_flash_borrower_package_hash";
+
+const CONTRACT_TEST_WCSPR_V1: &str = "test_wcspr_v1.wasm";
+const CONTRACT_TEST_QUERY: &str = "test_query.wasm";
 
 fn blake2b256(item_key_string: &[u8]) -> Box<[u8]> {
     let mut hasher = VarBlake2b::new(32).unwrap();
@@ -24,40 +45,193 @@ fn blake2b256(item_key_string: &[u8]) -> Box<[u8]> {
 #[derive(Clone, Copy)]
 pub struct Sender(pub AccountHash);
 
+#[derive(Debug, PartialEq)]
+pub enum Event {
+    Deposit {
+        owner: Key,
+        amount: U256,
+        fee: U256,
+    },
+    Withdrawal {
+        owner: Key,
+        amount: U256,
+        fee: U256,
+    },
+    Transfer {
+        sender: Key,
+        recipient: Key,
+        amount: U256,
+    },
+    Approval {
+        owner: Key,
+        spender: Key,
+        amount: U256,
+    },
+    FlashLoan {
+        receiver: Key,
+        amount: U256,
+        fee: U256,
+    },
+    Frozen {
+        address: Key,
+    },
+    Unfrozen {
+        address: Key,
+    },
+    WithdrawalsAutoPaused {
+        capacity: U256,
+    },
+}
+
+impl Event {
+    fn parse(bytes: &[u8]) -> Event {
+        let (name, bytes) = String::from_bytes(bytes).unwrap();
+        // Circuit breaker event does not start with an address
+        if name == "event_WithdrawalsAutoPaused" {
+            return Event::WithdrawalsAutoPaused {
+                capacity: U256::from_bytes(bytes).unwrap().0,
+            };
+        }
+        let (first, bytes) = Key::from_bytes(bytes).unwrap();
+        match name.as_str() {
+            "event_Deposit" => {
+                let (amount, bytes) = U256::from_bytes(bytes).unwrap();
+                Event::Deposit {
+                    owner: first,
+                    amount,
+                    fee: U256::from_bytes(bytes).unwrap().0,
+                }
+            }
+            "event_Withdrawal" => {
+                let (amount, bytes) = U256::from_bytes(bytes).unwrap();
+                Event::Withdrawal {
+                    owner: first,
+                    amount,
+                    fee: U256::from_bytes(bytes).unwrap().0,
+                }
+            }
+            "event_Transfer" => {
+                let (recipient, bytes) = Key::from_bytes(bytes).unwrap();
+                Event::Transfer {
+                    sender: first,
+                    recipient,
+                    amount: U256::from_bytes(bytes).unwrap().0,
+                }
+            }
+            "event_Approval" => {
+                let (spender, bytes) = Key::from_bytes(bytes).unwrap();
+                Event::Approval {
+                    owner: first,
+                    spender,
+                    amount: U256::from_bytes(bytes).unwrap().0,
+                }
+            }
+            "event_FlashLoan" => {
+                let (amount, bytes) = U256::from_bytes(bytes).unwrap();
+                Event::FlashLoan {
+                    receiver: first,
+                    amount,
+                    fee: U256::from_bytes(bytes).unwrap().0,
+                }
+            }
+            "event_Frozen" => Event::Frozen { address: first },
+            "event_Unfrozen" => Event::Unfrozen { address: first },
+            _ => panic!("unknown event {}", name),
+        }
+    }
+}
+
 pub struct TestFixture {
     context: TestContext,
     pub ali: AccountHash,
     pub bob: AccountHash,
     pub joe: AccountHash,
+    // Account that checks contract invariants after every step
+    checker: AccountHash,
+    // Accounts with known ed25519 and secp256k1 secret keys, used to sign permits
+    pub dan: AccountHash,
+    pub dan_key: PublicKey,
+    dan_secret: SecretKey,
+    pub eve: AccountHash,
+    pub eve_key: PublicKey,
+    eve_secret: SecretKey,
+    // Block time of the following sessions, in milliseconds
+    block_time: u64,
 }
 
 impl TestFixture {
     pub const TOKEN_NAME: &'static str = "Wrapped Casper";
     pub const TOKEN_SYMBOL: &'static str = "WCSPR";
     pub const TOKEN_DECIMALS: u8 = 9;
+    // 100 CSPR (100 * 10**9)
+    pub const USER_LIMIT: u64 = 100_000_000_000;
+    // 200 CSPR (200 * 10**9)
+    pub const CONTRACT_LIMIT: u64 = 200_000_000_000;
+    pub const CHAIN_NAME: &'static str = "casper-test";
     // const TOKEN_TOTAL_SUPPLY_AS_U64: u64 = 0;
 
     pub fn install_contract() -> TestFixture {
+        TestFixture::install(U256::zero(), false)
+    }
+
+    /// Installs contract that unwraps WCSPR sent to the contract package instead of reverting.
+    pub fn install_contract_unwrapping_self_transfers() -> TestFixture {
+        TestFixture::install(U256::zero(), true)
+    }
+
+    /// Installs contract passing `initial_supply` as `total_supply` argument.
+    pub fn install_contract_with_initial_supply(initial_supply: U256) -> TestFixture {
+        TestFixture::install(initial_supply, false)
+    }
+
+    fn install(initial_supply: U256, unwrap_self_transfers: bool) -> TestFixture {
+        let session_args = TestFixture::install_args(initial_supply, unwrap_self_transfers);
+        TestFixture::install_code(CONTRACT_WCSPR_TOKEN, session_args)
+    }
+
+    fn install_args(initial_supply: U256, unwrap_self_transfers: bool) -> RuntimeArgs {
+        runtime_args! {
+            consts::NAME_RUNTIME_ARG_NAME => TestFixture::TOKEN_NAME,
+            consts::SYMBOL_RUNTIME_ARG_NAME => TestFixture::TOKEN_SYMBOL,
+            consts::DECIMALS_RUNTIME_ARG_NAME => TestFixture::TOKEN_DECIMALS,
+            consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => initial_supply,
+            "user_limit" => U256::from(TestFixture::USER_LIMIT),
+            "contract_limit" => U256::from(TestFixture::CONTRACT_LIMIT),
+            "chain_name" => TestFixture::CHAIN_NAME,
+            "unwrap_self_transfers" => unwrap_self_transfers
+            // consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => U256::from(TestFixture::TOKEN_TOTAL_SUPPLY_AS_U64)
+            // consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => TestFixture::token_total_supply()
+        }
+    }
+
+    /// Installs contract with storage layout of version 1 and `cspr_amount` of WCSPR held by `ali`.
+    pub fn install_contract_v1(cspr_amount: U512) -> TestFixture {
+        let session_args = runtime_args! {
+            "cspr_amount" => cspr_amount,
+            "user_limit" => U256::from(TestFixture::USER_LIMIT),
+            "contract_limit" => U256::from(TestFixture::CONTRACT_LIMIT),
+        };
+        TestFixture::install_code(CONTRACT_TEST_WCSPR_V1, session_args)
+    }
+
+    fn install_code(wasm: &str, session_args: RuntimeArgs) -> TestFixture {
         println!("DEBUG MESSAGE: install contract");
         let ali = PublicKey::ed25519_from_bytes([3u8; 32]).unwrap();
         let bob = PublicKey::ed25519_from_bytes([6u8; 32]).unwrap();
         let joe = PublicKey::ed25519_from_bytes([9u8; 32]).unwrap();
+        let checker = PublicKey::ed25519_from_bytes([15u8; 32]).unwrap();
+        let dan_secret = SecretKey::ed25519_from_bytes([12u8; 32]).unwrap();
+        let dan = PublicKey::from(&dan_secret);
+        let eve_secret = SecretKey::secp256k1_from_bytes([18u8; 32]).unwrap();
+        let eve = PublicKey::from(&eve_secret);
 
         let mut context = TestContextBuilder::new()
             .with_public_key(ali.clone(), U512::from(500_000_000_000_000_000u64))
             .with_public_key(bob.clone(), U512::from(500_000_000_000_000_000u64))
+            .with_public_key(checker.clone(), U512::from(500_000_000_000_000_000u64))
             .build();
 
-        let session_code = Code::from(CONTRACT_WCSPR_TOKEN);
-        let session_args = runtime_args! {
-            consts::NAME_RUNTIME_ARG_NAME => TestFixture::TOKEN_NAME,
-            consts::SYMBOL_RUNTIME_ARG_NAME => TestFixture::TOKEN_SYMBOL,
-            consts::DECIMALS_RUNTIME_ARG_NAME => TestFixture::TOKEN_DECIMALS,
-            consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => U256::from(0)
-            // consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => U256::from(TestFixture::TOKEN_TOTAL_SUPPLY_AS_U64)
-            // consts::TOTAL_SUPPLY_RUNTIME_ARG_NAME => TestFixture::token_total_supply()
-        };
-
+        let session_code = Code::from(wasm);
         let session = SessionBuilder::new(session_code, session_args)
             .with_address(ali.to_account_hash())
             .with_authorization_keys(&[ali.to_account_hash()])
@@ -69,10 +243,18 @@ impl TestFixture {
             ali: ali.to_account_hash(),
             bob: bob.to_account_hash(),
             joe: joe.to_account_hash(),
+            checker: checker.to_account_hash(),
+            dan: dan.to_account_hash(),
+            dan_key: dan,
+            dan_secret,
+            eve: eve.to_account_hash(),
+            eve_key: eve,
+            eve_secret,
+            block_time: 0,
         }
     }
 
-    fn contract_hash(&self) -> ContractHash {
+    pub fn contract_hash(&self) -> ContractHash {
         self.context
             .get_account(self.ali)
             .unwrap()
@@ -85,6 +267,15 @@ impl TestFixture {
             .into()
     }
 
+    /// Address of the WCSPR contract package.
+    pub fn wcspr_package(&self) -> Key {
+        Key::Hash(
+            self.account_named_key(CONTRACT_PACKAGE_KEY_NAME)
+                .into_hash()
+                .unwrap(),
+        )
+    }
+
     fn query_contract<T: CLTyped + FromBytes>(&self, name: &str) -> Option<T> {
         match self
             .context
@@ -100,14 +291,91 @@ impl TestFixture {
         }
     }
 
+    /// Sets block time of the following sessions, in milliseconds.
+    pub fn set_block_time(&mut self, block_time: u64) {
+        self.block_time = block_time;
+    }
+
+    /// Returns `true` if the contract has `name` named key, e.g. a dictionary added by a migration.
+    pub fn has_contract_key(&self, name: &str) -> bool {
+        self.context
+            .query(self.ali, &[CONTRACT_KEY_NAME.to_string(), name.to_string()])
+            .is_ok()
+    }
+
+    /// Runs `session` and checks that contract invariants still hold after it.
+    fn run(&mut self, session: Session) {
+        self.context.run(session);
+        self.check_invariants();
+    }
+
+    /// Runs `session` the same way as [`TestFixture::run`], but returns the error of a failed
+    /// session instead of panicking, so the state after it could be checked.
+    ///
+    /// Failed session is not committed, the error is the panic message of the test context, e.g.
+    /// containing `ApiError::User(65534) [131070]`.
+    fn try_run(&mut self, session: Session) -> Result<(), String> {
+        let context = &mut self.context;
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            context.run(session);
+        }))
+        .map_err(|payload| match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload
+                .downcast::<&str>()
+                .map(|message| message.to_string())
+                .unwrap_or_default(),
+        })?;
+        self.check_invariants();
+        Ok(())
+    }
+
+    /// Calls `check_invariants` entry point, that reverts if total supply exceeds CSPR reserves.
+    ///
+    /// Called by a separate account, so gas does not change CSPR balances of test accounts.
+    pub fn check_invariants(&mut self) {
+        let code = Code::Hash(self.contract_hash().value(), "check_invariants".to_string());
+        let session = SessionBuilder::new(code, runtime_args! {})
+            .with_address(self.checker)
+            .with_authorization_keys(&[self.checker])
+            .with_block_time(self.block_time)
+            .build();
+        self.context.run(session);
+    }
+
+    pub fn sync(&mut self, sender: Sender) {
+        self.call(sender, "sync", runtime_args! {})
+    }
+
+    pub fn skim(&mut self, recipient: AccountHash, sender: Sender) {
+        self.call(
+            sender,
+            "skim",
+            runtime_args! {
+                "recipient" => recipient,
+            },
+        )
+    }
+
     fn call(&mut self, sender: Sender, method: &str, args: RuntimeArgs) {
+        let session = self.call_session(sender, method, args);
+        self.run(session);
+    }
+
+    /// Calls `method` the same way as [`TestFixture::call`], returning the error if it fails.
+    fn try_call(&mut self, sender: Sender, method: &str, args: RuntimeArgs) -> Result<(), String> {
+        let session = self.call_session(sender, method, args);
+        self.try_run(session)
+    }
+
+    fn call_session(&self, sender: Sender, method: &str, args: RuntimeArgs) -> Session {
         let Sender(address) = sender;
         let code = Code::Hash(self.contract_hash().value(), method.to_string());
-        let session = SessionBuilder::new(code, args)
+        SessionBuilder::new(code, args)
             .with_address(address)
             .with_authorization_keys(&[address])
-            .build();
-        self.context.run(session);
+            .with_block_time(self.block_time)
+            .build()
     }
 
     pub fn token_total_supply(&self) -> U256 {
@@ -120,6 +388,24 @@ impl TestFixture {
         self.query_contract("cspr_balance").unwrap()
     }
 
+    pub fn storage_version(&self) -> u32 {
+        self.query_contract("storage_version").unwrap()
+    }
+
+    /// CSPR balance of the `account` main purse.
+    pub fn account_cspr_balance(&self, account: AccountHash) -> U512 {
+        let purse_addr = self.context.main_purse_address(account).unwrap();
+        self.context.get_balance(purse_addr)
+    }
+
+    pub fn user_limit(&self) -> U256 {
+        self.query_contract("user_limit").unwrap()
+    }
+
+    pub fn contract_limit(&self) -> U256 {
+        self.query_contract("contract_limit").unwrap()
+    }
+
     pub fn token_name(&self) -> String {
         self.query_contract(consts::NAME_RUNTIME_ARG_NAME).unwrap()
     }
@@ -167,6 +453,174 @@ impl TestFixture {
         Some(value.into_t::<U256>().unwrap())
     }
 
+    pub fn has_role(&self, role: &str, account: Key) -> bool {
+        let item_key = base64::encode(&account.to_bytes().unwrap());
+
+        let key = Key::Hash(self.contract_hash().value());
+        self.context
+            .query_dictionary_item(key, Some(format!("{}_role", role)), item_key)
+            .ok()
+            .map(|value| value.into_t::<bool>().unwrap())
+            .unwrap_or(false)
+    }
+
+    pub fn grant_role(&mut self, role: &str, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "grant_role",
+            runtime_args! {
+                "role" => role.to_string(),
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+            },
+        )
+    }
+
+    pub fn revoke_role(&mut self, role: &str, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "revoke_role",
+            runtime_args! {
+                "role" => role.to_string(),
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+            },
+        )
+    }
+
+    pub fn is_paused(&self, operation: &str) -> bool {
+        self.query_contract(&format!("{}_paused", operation))
+            .unwrap()
+    }
+
+    pub fn pause(&mut self, operations: &[&str], sender: Sender) {
+        let operations: Vec<String> = operations.iter().map(|op| op.to_string()).collect();
+        self.call(
+            sender,
+            "pause",
+            runtime_args! {
+                "operations" => operations,
+            },
+        )
+    }
+
+    pub fn unpause(&mut self, operations: &[&str], sender: Sender) {
+        let operations: Vec<String> = operations.iter().map(|op| op.to_string()).collect();
+        self.call(
+            sender,
+            "unpause",
+            runtime_args! {
+                "operations" => operations,
+            },
+        )
+    }
+
+    pub fn events_length(&self) -> u32 {
+        self.query_contract("__events_length").unwrap()
+    }
+
+    pub fn event(&self, index: u32) -> Event {
+        let key = Key::Hash(self.contract_hash().value());
+        let value = self
+            .context
+            .query_dictionary_item(key, Some("__events".to_string()), index.to_string())
+            .unwrap();
+        Event::parse(&value.into_t::<Bytes>().unwrap())
+    }
+
+    pub fn last_event(&self) -> Event {
+        self.event(self.events_length() - 1)
+    }
+
+    pub fn nonce(&self, owner: &PublicKey) -> u64 {
+        let item_key = base64::encode(&Key::from(owner.to_account_hash()).to_bytes().unwrap());
+
+        let key = Key::Hash(self.contract_hash().value());
+        self.context
+            .query_dictionary_item(key, Some("nonces".to_string()), item_key)
+            .ok()
+            .map(|value| value.into_t::<u64>().unwrap())
+            .unwrap_or_default()
+    }
+
+    /// Signs permit of `dan` the same way as a wallet would do off-chain.
+    /// Signs permit of `dan`, owner with an ed25519 key.
+    pub fn sign_permit(&self, spender: Key, amount: U256, nonce: u64, deadline: u64) -> Bytes {
+        self.sign_permit_with(
+            &self.dan_secret,
+            &self.dan_key,
+            spender,
+            amount,
+            nonce,
+            deadline,
+        )
+    }
+
+    /// Signs permit of `eve`, owner with a secp256k1 key.
+    pub fn sign_secp256k1_permit(
+        &self,
+        spender: Key,
+        amount: U256,
+        nonce: u64,
+        deadline: u64,
+    ) -> Bytes {
+        self.sign_permit_with(
+            &self.eve_secret,
+            &self.eve_key,
+            spender,
+            amount,
+            nonce,
+            deadline,
+        )
+    }
+
+    fn sign_permit_with(
+        &self,
+        secret: &SecretKey,
+        owner: &PublicKey,
+        spender: Key,
+        amount: U256,
+        nonce: u64,
+        deadline: u64,
+    ) -> Bytes {
+        let mut domain = Vec::new();
+        domain.append(&mut "WCSPR permit".to_string().to_bytes().unwrap());
+        domain.append(&mut TestFixture::CHAIN_NAME.to_string().to_bytes().unwrap());
+        domain.append(&mut self.contract_hash().to_bytes().unwrap());
+
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&blake2b256(&domain));
+        preimage.append(&mut owner.to_bytes().unwrap());
+        preimage.append(&mut spender.to_bytes().unwrap());
+        preimage.append(&mut amount.to_bytes().unwrap());
+        preimage.append(&mut nonce.to_bytes().unwrap());
+        preimage.append(&mut deadline.to_bytes().unwrap());
+        let digest = blake2b256(&preimage);
+
+        let signature = crypto::sign(&digest, secret, owner);
+        Bytes::from(signature.to_bytes().unwrap())
+    }
+
+    pub fn permit(
+        &mut self,
+        owner: PublicKey,
+        spender: Key,
+        amount: U256,
+        deadline: u64,
+        signature: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "permit",
+            runtime_args! {
+                consts::OWNER_RUNTIME_ARG_NAME => owner,
+                consts::SPENDER_RUNTIME_ARG_NAME => spender,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount,
+                "deadline" => deadline,
+                "signature" => signature,
+            },
+        )
+    }
+
     pub fn transfer(&mut self, recipient: Key, amount: U256, sender: Sender) {
         self.call(
             sender,
@@ -178,6 +632,42 @@ impl TestFixture {
         );
     }
 
+    pub fn batch_transfer(&mut self, recipients: &[(Key, U256)], sender: Sender) {
+        self.call(
+            sender,
+            "batch_transfer",
+            runtime_args! {
+                "recipients" => recipients.to_vec(),
+            },
+        );
+    }
+
+    /// Runs `batch_transfer`, returning the error if the batch fails.
+    pub fn try_batch_transfer(
+        &mut self,
+        recipients: &[(Key, U256)],
+        sender: Sender,
+    ) -> Result<(), String> {
+        self.try_call(
+            sender,
+            "batch_transfer",
+            runtime_args! {
+                "recipients" => recipients.to_vec(),
+            },
+        )
+    }
+
+    pub fn batch_transfer_from(&mut self, owner: Key, recipients: &[(Key, U256)], sender: Sender) {
+        self.call(
+            sender,
+            "batch_transfer_from",
+            runtime_args! {
+                consts::OWNER_RUNTIME_ARG_NAME => owner,
+                "recipients" => recipients.to_vec(),
+            },
+        );
+    }
+
     pub fn approve(&mut self, spender: Key, amount: U256, sender: Sender) {
         self.call(
             sender,
@@ -189,6 +679,46 @@ impl TestFixture {
         );
     }
 
+    pub fn increase_allowance(&mut self, spender: Key, amount: U256, sender: Sender) {
+        self.call(
+            sender,
+            "increase_allowance",
+            runtime_args! {
+                consts::SPENDER_RUNTIME_ARG_NAME => spender,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount
+            },
+        );
+    }
+
+    pub fn decrease_allowance(&mut self, spender: Key, amount: U256, sender: Sender) {
+        self.call(
+            sender,
+            "decrease_allowance",
+            runtime_args! {
+                consts::SPENDER_RUNTIME_ARG_NAME => spender,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount
+            },
+        );
+    }
+
+    pub fn approve_if(
+        &mut self,
+        spender: Key,
+        expected_current: U256,
+        amount: U256,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "approve_if",
+            runtime_args! {
+                consts::SPENDER_RUNTIME_ARG_NAME => spender,
+                "expected_current" => expected_current,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount
+            },
+        );
+    }
+
     pub fn transfer_from(&mut self, owner: Key, recipient: Key, amount: U256, sender: Sender) {
         self.call(
             sender,
@@ -213,8 +743,351 @@ impl TestFixture {
         )
         .with_address(address)
         .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
         .build();
-        self.context.run(session);
+        self.run(session);
+    }
+
+    pub fn snapshot(&mut self, sender: Sender) {
+        self.call(sender, "snapshot", runtime_args! {})
+    }
+
+    pub fn current_snapshot_id(&self) -> u64 {
+        self.query_contract("current_snapshot_id").unwrap()
+    }
+
+    /// Calls read-only `entry_point` of the contract returning U256 through the test query
+    /// session code, returning the error if the entry point reverts.
+    ///
+    /// Run by a separate account, so gas does not change CSPR balances of test accounts.
+    /// Calls `entry_point` returning `result_type` value through the query session code.
+    fn try_query<T: CLTyped + FromBytes>(
+        &mut self,
+        entry_point: &str,
+        args: RuntimeArgs,
+        result_type: &str,
+    ) -> Result<T, String> {
+        let code = Code::from(CONTRACT_TEST_QUERY);
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "entry_point" => entry_point,
+                "result_type" => result_type,
+                "args" => Bytes::from(args.to_bytes().unwrap()),
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash())
+            },
+        )
+        .with_address(self.checker)
+        .with_authorization_keys(&[self.checker])
+        .with_block_time(self.block_time)
+        .build();
+        self.try_run(session)?;
+
+        Ok(self
+            .context
+            .query(self.checker, &["query_result".to_string()])
+            .unwrap()
+            .into_t()
+            .unwrap())
+    }
+
+    fn try_query_u256(&mut self, entry_point: &str, args: RuntimeArgs) -> Result<U256, String> {
+        self.try_query(entry_point, args, "U256")
+    }
+
+    fn query_u256(&mut self, entry_point: &str, args: RuntimeArgs) -> U256 {
+        self.try_query_u256(entry_point, args)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn try_balance_of_at(&mut self, account: Key, snapshot_id: u64) -> Result<U256, String> {
+        self.try_query_u256(
+            "balance_of_at",
+            runtime_args! {
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+                "snapshot_id" => snapshot_id,
+            },
+        )
+    }
+
+    pub fn balance_of_at(&mut self, account: Key, snapshot_id: u64) -> U256 {
+        self.try_balance_of_at(account, snapshot_id)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn total_supply_at(&mut self, snapshot_id: u64) -> U256 {
+        self.query_u256(
+            "total_supply_at",
+            runtime_args! {
+                "snapshot_id" => snapshot_id,
+            },
+        )
+    }
+
+    pub fn delegate(&mut self, delegatee: Key, sender: Sender) {
+        self.call(
+            sender,
+            "delegate",
+            runtime_args! {
+                "delegatee" => delegatee,
+            },
+        )
+    }
+
+    pub fn delegates(&self, account: Key) -> Option<Key> {
+        let item_key = base64::encode(&account.to_bytes().unwrap());
+
+        let key = Key::Hash(self.contract_hash().value());
+        self.context
+            .query_dictionary_item(key, Some("delegates".to_string()), item_key)
+            .ok()
+            .map(|value| value.into_t().unwrap())
+    }
+
+    pub fn get_votes(&mut self, account: Key) -> U256 {
+        self.query_u256(
+            "get_votes",
+            runtime_args! {
+                "account" => account,
+            },
+        )
+    }
+
+    /// Votes of `account` at the end of `timestamp` block time, that should be before the block
+    /// time set by [`TestFixture::set_block_time`].
+    pub fn try_get_past_votes(&mut self, account: Key, timestamp: u64) -> Result<U256, String> {
+        self.try_query_u256(
+            "get_past_votes",
+            runtime_args! {
+                "account" => account,
+                "timestamp" => timestamp,
+            },
+        )
+    }
+
+    pub fn get_past_votes(&mut self, account: Key, timestamp: u64) -> U256 {
+        self.try_get_past_votes(account, timestamp)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn is_frozen(&self, account: Key) -> bool {
+        let item_key = base64::encode(&account.to_bytes().unwrap());
+
+        let key = Key::Hash(self.contract_hash().value());
+        self.context
+            .query_dictionary_item(key, Some("frozen".to_string()), item_key)
+            .ok()
+            .map(|value| value.into_t::<bool>().unwrap())
+            .unwrap_or(false)
+    }
+
+    pub fn freeze(&mut self, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "freeze",
+            runtime_args! {
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+            },
+        )
+    }
+
+    pub fn unfreeze(&mut self, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "unfreeze",
+            runtime_args! {
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+            },
+        )
+    }
+
+    pub fn set_withdrawal_limit(
+        &mut self,
+        window: u64,
+        limit: U512,
+        pause_floor: U512,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "set_withdrawal_limit",
+            runtime_args! {
+                "window" => window,
+                "limit" => limit,
+                "pause_floor" => pause_floor,
+            },
+        )
+    }
+
+    /// CSPR that could still be withdrawn at the current block time.
+    pub fn withdrawal_capacity(&mut self) -> U512 {
+        self.try_query("withdrawal_capacity", runtime_args! {}, "U512")
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    pub fn fee_config(&self) -> (u32, u32, AccountHash) {
+        (
+            self.query_contract("deposit_fee_bps").unwrap(),
+            self.query_contract("withdraw_fee_bps").unwrap(),
+            self.query_contract("treasury").unwrap(),
+        )
+    }
+
+    pub fn set_fee_config(
+        &mut self,
+        deposit_fee_bps: u32,
+        withdraw_fee_bps: u32,
+        treasury: AccountHash,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "set_fee_config",
+            runtime_args! {
+                "deposit_fee_bps" => deposit_fee_bps,
+                "withdraw_fee_bps" => withdraw_fee_bps,
+                "treasury" => treasury,
+            },
+        )
+    }
+
+    pub fn set_limits(&mut self, user_limit: U256, contract_limit: U256, sender: Sender) {
+        self.call(
+            sender,
+            "set_limits",
+            runtime_args! {
+                "user_limit" => user_limit,
+                "contract_limit" => contract_limit,
+            },
+        )
+    }
+
+    pub fn set_allowlist_enabled(&mut self, enabled: bool, sender: Sender) {
+        self.call(
+            sender,
+            "set_allowlist_enabled",
+            runtime_args! {
+                "enabled" => enabled,
+            },
+        )
+    }
+
+    pub fn set_tier_caps(
+        &mut self,
+        tier: u8,
+        max_holding: U256,
+        max_deposit: U256,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "set_tier_caps",
+            runtime_args! {
+                "tier" => tier,
+                "max_holding" => max_holding,
+                "max_deposit" => max_deposit,
+            },
+        )
+    }
+
+    pub fn set_address_tier(&mut self, account: Key, tier: u8, sender: Sender) {
+        self.call(
+            sender,
+            "set_address_tier",
+            runtime_args! {
+                consts::ADDRESS_RUNTIME_ARG_NAME => account,
+                "tier" => tier,
+            },
+        )
+    }
+
+    pub fn tier_of(&self, account: Key) -> u8 {
+        let item_key = base64::encode(&account.to_bytes().unwrap());
+
+        let key = Key::Hash(self.contract_hash().value());
+        self.context
+            .query_dictionary_item(key, Some("address_tiers".to_string()), item_key)
+            .ok()
+            .map(|value| value.into_t::<u8>().unwrap())
+            .unwrap_or(0)
+    }
+
+    /// Runs install session code again, that upgrades the package installed by `sender`.
+    pub fn upgrade(&mut self, sender: Sender) {
+        let Sender(address) = sender;
+        let code = Code::from(CONTRACT_WCSPR_TOKEN);
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "chain_name" => TestFixture::CHAIN_NAME,
+            },
+        )
+        .with_address(address)
+        .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
+        .build();
+        self.run(session);
+    }
+
+    pub fn deposit_for(&mut self, recipient: Key, cspr_amount: U512, sender: Sender) {
+        let Sender(address) = sender;
+        let code = Code::from(CONTRACT_PRE_DEPOSIT_FOR);
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "cspr_amount" => cspr_amount,
+                "recipient" => recipient,
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash())
+            },
+        )
+        .with_address(address)
+        .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
+        .build();
+        self.run(session);
+    }
+
+    pub fn wrap_and_transfer(&mut self, recipient: Key, cspr_amount: U512, sender: Sender) {
+        let Sender(address) = sender;
+        let code = Code::from(CONTRACT_WRAP_AND_TRANSFER);
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "cspr_amount" => cspr_amount,
+                "recipient" => recipient,
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash())
+            },
+        )
+        .with_address(address)
+        .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
+        .build();
+        self.run(session);
+    }
+
+    pub fn wrap_and_approve(
+        &mut self,
+        spender: Key,
+        amount: Option<U256>,
+        cspr_amount: U512,
+        sender: Sender,
+    ) {
+        let Sender(address) = sender;
+        let code = Code::from(CONTRACT_WRAP_AND_APPROVE);
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "cspr_amount" => cspr_amount,
+                "spender" => spender,
+                "amount" => amount,
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash())
+            },
+        )
+        .with_address(address)
+        .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
+        .build();
+        self.run(session);
     }
 
     pub fn withdraw(&mut self, sender: Sender, cspr_amount: U512) {
@@ -226,4 +1099,237 @@ impl TestFixture {
             },
         )
     }
+
+    pub fn try_withdraw(&mut self, sender: Sender, cspr_amount: U512) -> Result<(), String> {
+        self.try_call(
+            sender,
+            "withdraw",
+            runtime_args! {
+                "cspr_amount" => cspr_amount,
+            },
+        )
+    }
+
+    pub fn withdraw_to(&mut self, recipient: AccountHash, cspr_amount: U512, sender: Sender) {
+        self.call(
+            sender,
+            "withdraw_to",
+            runtime_args! {
+                "recipient" => recipient,
+                "cspr_amount" => cspr_amount,
+            },
+        )
+    }
+
+    fn install_helper_contract(&mut self, wasm: &str) {
+        let code = Code::from(wasm);
+        let session = SessionBuilder::new(code, runtime_args! {})
+            .with_address(self.ali)
+            .with_authorization_keys(&[self.ali])
+            .with_block_time(self.block_time)
+            .build();
+        self.run(session);
+    }
+
+    /// Installs helper contract that unwraps WCSPR into its own purse.
+    pub fn install_test_vault(&mut self) {
+        self.install_helper_contract(CONTRACT_TEST_VAULT);
+    }
+
+    fn account_named_key(&self, name: &str) -> Key {
+        *self
+            .context
+            .get_account(self.ali)
+            .unwrap()
+            .named_keys()
+            .get(name)
+            .unwrap()
+    }
+
+    /// Address of the helper vault contract package.
+    pub fn test_vault(&self) -> Key {
+        Key::Hash(
+            self.account_named_key(TEST_VAULT_PACKAGE_KEY_NAME)
+                .into_hash()
+                .unwrap(),
+        )
+    }
+
+    pub fn test_vault_unwrap(&mut self, cspr_amount: U512, sender: Sender) {
+        let Sender(address) = sender;
+        let vault_hash = self
+            .account_named_key(TEST_VAULT_KEY_NAME)
+            .into_hash()
+            .unwrap();
+        let code = Code::Hash(vault_hash, "unwrap".to_string());
+        let session = SessionBuilder::new(
+            code,
+            runtime_args! {
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash()),
+                "cspr_amount" => cspr_amount,
+            },
+        )
+        .with_address(address)
+        .with_authorization_keys(&[address])
+        .with_block_time(self.block_time)
+        .build();
+        self.run(session);
+    }
+
+    /// CSPR balance of the helper vault purse saved by its last unwrap.
+    pub fn test_vault_cspr_balance(&self) -> U512 {
+        self.context
+            .query(
+                self.ali,
+                &[TEST_VAULT_KEY_NAME.to_string(), "cspr_balance".to_string()],
+            )
+            .unwrap()
+            .into_t()
+            .unwrap()
+    }
+
+    pub fn max_flash_loan(&self) -> U256 {
+        self.query_contract("max_flash_loan").unwrap()
+    }
+
+    pub fn flash_fee_bps(&self) -> u32 {
+        self.query_contract("flash_fee_bps").unwrap()
+    }
+
+    pub fn set_flash_loan_config(&mut self, fee_bps: u32, max_amount: U256, sender: Sender) {
+        self.call(
+            sender,
+            "set_flash_loan_config",
+            runtime_args! {
+                "fee_bps" => fee_bps,
+                "max_amount" => max_amount,
+            },
+        )
+    }
+
+    pub fn flash_loan(&mut self, receiver: Key, amount: U256, sender: Sender) {
+        let receiver = ContractPackageHash::new(receiver.into_hash().unwrap());
+        self.call(
+            sender,
+            "flash_loan",
+            runtime_args! {
+                "receiver" => receiver,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount,
+                "data" => Bytes::new(),
+            },
+        )
+    }
+
+    /// Installs helper contract that receives flash loans.
+    pub fn install_test_flash_borrower(&mut self) {
+        self.install_helper_contract(CONTRACT_TEST_FLASH_BORROWER);
+    }
+
+    /// Address of the helper flash borrower contract package.
+    pub fn test_flash_borrower(&self) -> Key {
+        Key::Hash(
+            self.account_named_key(TEST_FLASH_BORROWER_PACKAGE_KEY_NAME)
+                .into_hash()
+                .unwrap(),
+        )
+    }
+
+    /// Amount borrowed by the helper flash borrower on its last `on_flash_loan` call.
+    pub fn test_flash_borrower_last_loan(&self) -> U256 {
+        self.context
+            .query(
+                self.ali,
+                &[
+                    TEST_FLASH_BORROWER_KEY_NAME.to_string(),
+                    "last_loan_amount".to_string(),
+                ],
+            )
+            .unwrap()
+            .into_t()
+            .unwrap()
+    }
+}
+
+/// Measures gas of the contract entry points.
+///
+/// `TestContext` does not expose gas costs of executed deploys, so the contract is installed with
+/// the in-memory builder of the test support crate, by the default genesis account.
+pub struct GasFixture {
+    builder: InMemoryWasmTestBuilder,
+    contract_hash: ContractHash,
+}
+
+impl GasFixture {
+    pub fn install_contract() -> GasFixture {
+        let mut builder = InMemoryWasmTestBuilder::default();
+        builder.run_genesis(&*DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+        let install_request = ExecuteRequestBuilder::standard(
+            *DEFAULT_ACCOUNT_ADDR,
+            CONTRACT_WCSPR_TOKEN,
+            TestFixture::install_args(U256::zero(), false),
+        )
+        .build();
+        builder.exec(install_request).expect_success().commit();
+
+        let contract_hash = builder
+            .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+            .named_keys()
+            .get(CONTRACT_KEY_NAME)
+            .unwrap()
+            .normalize()
+            .into_hash()
+            .unwrap()
+            .into();
+        GasFixture {
+            builder,
+            contract_hash,
+        }
+    }
+
+    /// Runs `request` of the genesis account and returns its gas cost.
+    fn exec(&mut self, request_builder: ExecuteRequestBuilder) -> U512 {
+        self.builder
+            .exec(request_builder.build())
+            .expect_success()
+            .commit();
+        self.builder.last_exec_gas_cost().value()
+    }
+
+    pub fn deposit(&mut self, cspr_amount: U512) -> U512 {
+        let request_builder = ExecuteRequestBuilder::standard(
+            *DEFAULT_ACCOUNT_ADDR,
+            CONTRACT_PRE_DEPOSIT,
+            runtime_args! {
+                "cspr_amount" => cspr_amount,
+                "wcspr_contract_hash_key" => Key::from(self.contract_hash)
+            },
+        );
+        self.exec(request_builder)
+    }
+
+    pub fn transfer(&mut self, recipient: Key, amount: U256) -> U512 {
+        let request_builder = ExecuteRequestBuilder::contract_call_by_hash(
+            *DEFAULT_ACCOUNT_ADDR,
+            self.contract_hash,
+            consts::TRANSFER_ENTRY_POINT_NAME,
+            runtime_args! {
+                consts::RECIPIENT_RUNTIME_ARG_NAME => recipient,
+                consts::AMOUNT_RUNTIME_ARG_NAME => amount,
+            },
+        );
+        self.exec(request_builder)
+    }
+
+    pub fn batch_transfer(&mut self, recipients: &[(Key, U256)]) -> U512 {
+        let request_builder = ExecuteRequestBuilder::contract_call_by_hash(
+            *DEFAULT_ACCOUNT_ADDR,
+            self.contract_hash,
+            "batch_transfer",
+            runtime_args! {
+                "recipients" => recipients.to_vec(),
+            },
+        );
+        self.exec(request_builder)
+    }
 }